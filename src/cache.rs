@@ -0,0 +1,326 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use nu_protocol::Value;
+
+use crate::segments::Segment;
+use crate::status::GitStatus;
+use crate::time::now;
+
+/// Last output of every expensive segment, per repository.
+///
+/// The plugin process stays alive between prompt renders, so an expensive
+/// segment whose cache key didn't change can reuse what it rendered last time.
+#[derive(Debug, Default)]
+pub struct SegmentCache {
+    entries: Mutex<HashMap<(PathBuf, &'static str), CacheEntry>>,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    key: String,
+    output: Option<String>,
+}
+
+impl SegmentCache {
+    /// Returns the cached output for `segment` if it was rendered with `key`.
+    pub fn get(&self, git_dir: &Path, segment: &'static str, key: &str) -> Option<Option<String>> {
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(&(git_dir.to_path_buf(), segment))?;
+        if entry.key != key {
+            return None;
+        }
+
+        Some(entry.output.clone())
+    }
+
+    pub fn insert(
+        &self,
+        git_dir: &Path,
+        segment: &'static str,
+        key: String,
+        output: Option<String>,
+    ) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert((git_dir.to_path_buf(), segment), CacheEntry { key, output });
+        }
+    }
+
+    /// Forgets the outputs of the repository at `git_dir`, or of every
+    /// repository when it's `None`.
+    pub fn clear(&self, git_dir: Option<&Path>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            match git_dir {
+                Some(git_dir) => entries.retain(|(dir, _), _| dir != git_dir),
+                None => entries.clear(),
+            }
+        }
+    }
+}
+
+/// Last prompt fully rendered for every repository and set of segments, shown
+/// by the `cached` degrade strategy once the repository is over
+/// `max_repo_size`.
+#[derive(Debug, Default)]
+pub struct PromptCache {
+    entries: Mutex<HashMap<(PathBuf, String), Vec<Segment>>>,
+}
+
+impl PromptCache {
+    pub fn get(&self, git_dir: &Path, names: &[String]) -> Option<Vec<Segment>> {
+        let entries = self.entries.lock().ok()?;
+        entries
+            .get(&(git_dir.to_path_buf(), names.join(" ")))
+            .cloned()
+    }
+
+    pub fn insert(&self, git_dir: &Path, names: &[String], segments: Vec<Segment>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert((git_dir.to_path_buf(), names.join(" ")), segments);
+        }
+    }
+
+    /// Forgets the prompts of the repository at `git_dir`, or of every
+    /// repository when it's `None`.
+    pub fn clear(&self, git_dir: Option<&Path>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            match git_dir {
+                Some(git_dir) => entries.retain(|(dir, _), _| dir != git_dir),
+                None => entries.clear(),
+            }
+        }
+    }
+}
+
+/// Last status scan of every repository.
+///
+/// The key only covers the git dir, so like the rows of the summary a status
+/// is only reused while it is younger than the configured TTL.
+#[derive(Debug, Default)]
+pub struct StatusCache {
+    entries: Mutex<HashMap<PathBuf, StatusEntry>>,
+    /// Git dirs whose status is being scanned in the background.
+    refreshing: Mutex<HashSet<PathBuf>>,
+}
+
+#[derive(Debug, Clone)]
+struct StatusEntry {
+    key: String,
+    created: i64,
+    status: GitStatus,
+}
+
+impl StatusCache {
+    /// Returns the cached status of the repository at `git_dir` if it was
+    /// scanned with `key` less than `ttl` seconds ago.
+    pub fn get(&self, git_dir: &Path, key: &str, ttl: i64) -> Option<GitStatus> {
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(git_dir)?;
+        if entry.key != key || now() - entry.created >= ttl {
+            return None;
+        }
+
+        Some(entry.status.clone())
+    }
+
+    /// Returns the last status of the repository at `git_dir`, however old.
+    pub fn last(&self, git_dir: &Path) -> Option<GitStatus> {
+        let entries = self.entries.lock().ok()?;
+        entries.get(git_dir).map(|entry| entry.status.clone())
+    }
+
+    pub fn insert(&self, git_dir: &Path, key: String, status: GitStatus) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(
+                git_dir.to_path_buf(),
+                StatusEntry {
+                    key,
+                    created: now(),
+                    status,
+                },
+            );
+        }
+    }
+
+    /// Marks the status of `git_dir` as being refreshed, `false` when it
+    /// already was.
+    pub fn start_refresh(&self, git_dir: &Path) -> bool {
+        self.refreshing
+            .lock()
+            .is_ok_and(|mut refreshing| refreshing.insert(git_dir.to_path_buf()))
+    }
+
+    pub fn finish_refresh(&self, git_dir: &Path) {
+        if let Ok(mut refreshing) = self.refreshing.lock() {
+            refreshing.remove(git_dir);
+        }
+    }
+
+    /// Forgets the status of the repository at `git_dir`, or of every
+    /// repository when it's `None`.
+    pub fn clear(&self, git_dir: Option<&Path>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            match git_dir {
+                Some(git_dir) => {
+                    entries.remove(git_dir);
+                }
+                None => entries.clear(),
+            }
+        }
+    }
+}
+
+/// Last row of `git_prompt summary` for every repository.
+///
+/// Working tree edits don't change any file of the git dir, so on top of the
+/// key a row is only reused while it is younger than the configured TTL.
+#[derive(Debug, Default)]
+pub struct RowCache {
+    entries: Mutex<HashMap<PathBuf, RowEntry>>,
+}
+
+#[derive(Debug, Clone)]
+struct RowEntry {
+    key: String,
+    created: i64,
+    row: Value,
+}
+
+impl RowCache {
+    /// Returns the cached row of `path` if it was computed with `key` less
+    /// than `ttl` seconds ago.
+    pub fn get(&self, path: &Path, key: &str, ttl: i64) -> Option<Value> {
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(path)?;
+        if entry.key != key || now() - entry.created >= ttl {
+            return None;
+        }
+
+        Some(entry.row.clone())
+    }
+
+    pub fn insert(&self, path: &Path, key: String, row: Value) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(
+                path.to_path_buf(),
+                RowEntry {
+                    key,
+                    created: now(),
+                    row,
+                },
+            );
+        }
+    }
+
+    /// Forgets the row of the working tree at `path`, or every row when it's
+    /// `None`.
+    pub fn clear(&self, path: Option<&Path>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            match path {
+                Some(path) => {
+                    entries.remove(path);
+                }
+                None => entries.clear(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_cache_needs_the_same_key() {
+        let cache = SegmentCache::default();
+        let repo = Path::new("/repo/.git");
+        let other = Path::new("/other/.git");
+        cache.insert(repo, "stash", "1".to_string(), Some("≡1".to_string()));
+        cache.insert(repo, "lock", "1".to_string(), None);
+        cache.insert(other, "stash", "1".to_string(), Some("≡2".to_string()));
+
+        assert_eq!(cache.get(repo, "stash", "1"), Some(Some("≡1".to_string())));
+        assert_eq!(cache.get(repo, "lock", "1"), Some(None));
+        assert_eq!(cache.get(repo, "stash", "2"), None);
+
+        cache.clear(Some(repo));
+        assert_eq!(cache.get(repo, "stash", "1"), None);
+        assert_eq!(cache.get(other, "stash", "1"), Some(Some("≡2".to_string())));
+
+        cache.clear(None);
+        assert_eq!(cache.get(other, "stash", "1"), None);
+    }
+
+    #[test]
+    fn prompt_cache_is_per_set_of_segments() {
+        let cache = PromptCache::default();
+        let repo = Path::new("/repo/.git");
+        let left = vec!["branch".to_string(), "stash".to_string()];
+        let right = vec!["age".to_string()];
+        let segments = vec![Segment {
+            name: "branch",
+            text: "main".to_string(),
+        }];
+        cache.insert(repo, &left, segments);
+
+        let cached = cache.get(repo, &left).unwrap();
+        assert_eq!(cached[0].text, "main");
+        assert!(cache.get(repo, &right).is_none());
+
+        cache.clear(Some(repo));
+        assert!(cache.get(repo, &left).is_none());
+    }
+
+    #[test]
+    fn status_cache_needs_the_same_key_and_a_ttl() {
+        let cache = StatusCache::default();
+        let git_dir = Path::new("/repo/.git");
+        let status = GitStatus {
+            wt_new: 1,
+            ..GitStatus::default()
+        };
+        cache.insert(git_dir, "head".to_string(), status);
+
+        assert_eq!(cache.get(git_dir, "head", 60).map(|s| s.wt_new), Some(1));
+        assert!(cache.get(git_dir, "moved", 60).is_none());
+        // The default TTL of 0 never reuses a scan.
+        assert!(cache.get(git_dir, "head", 0).is_none());
+        assert_eq!(cache.last(git_dir).map(|s| s.wt_new), Some(1));
+
+        cache.clear(Some(git_dir));
+        assert!(cache.last(git_dir).is_none());
+    }
+
+    #[test]
+    fn a_status_is_refreshed_once_at_a_time() {
+        let cache = StatusCache::default();
+        let git_dir = Path::new("/repo/.git");
+
+        assert!(cache.start_refresh(git_dir));
+        assert!(!cache.start_refresh(git_dir));
+        assert!(cache.start_refresh(Path::new("/other/.git")));
+
+        cache.finish_refresh(git_dir);
+        assert!(cache.start_refresh(git_dir));
+    }
+
+    #[test]
+    fn status_ttl_is_off_by_default() {
+        assert_eq!(crate::config::Config::default().status_ttl, 0);
+    }
+
+    #[test]
+    fn row_cache_needs_the_same_key_and_a_ttl() {
+        let cache = RowCache::default();
+        let path = Path::new("/repo");
+        cache.insert(path, "head".to_string(), Value::test_int(1));
+
+        assert_eq!(cache.get(path, "head", 60), Some(Value::test_int(1)));
+        assert!(cache.get(path, "moved", 60).is_none());
+        assert!(cache.get(path, "head", 0).is_none());
+
+        cache.clear(None);
+        assert!(cache.get(path, "head", 60).is_none());
+    }
+}
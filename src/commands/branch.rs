@@ -0,0 +1,75 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::target_dir;
+use crate::config::GitEnv;
+use crate::context::branch_name;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptBranch;
+
+impl SimplePluginCommand for GitPromptBranch {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt branch"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::String)])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to show the branch of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Name of the current branch, or the short commit id when HEAD is detached"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt branch",
+            description: "Show only the branch name, without the status",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let Some(dir) = target_dir(engine, call)? else {
+            return Ok(Value::string("", call.head));
+        };
+
+        let Ok(repo) = GitEnv::load(engine)?.discover(&dir) else {
+            return Ok(Value::string("", call.head));
+        };
+
+        Ok(Value::string(branch_name(&repo), call.head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn names_the_current_branch() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let source = format!("git_prompt branch --path {:?}", repo.path());
+        assert_eq!(eval(&source).unwrap().as_str().unwrap(), "main");
+
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        assert_eq!(eval(&source).unwrap().as_str().unwrap(), "");
+    }
+}
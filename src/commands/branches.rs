@@ -0,0 +1,157 @@
+use git2::BranchType;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{record, Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::discover_repo;
+use crate::time::age_value;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptBranches;
+
+impl SimplePluginCommand for GitPromptBranches {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt branches"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to list the branches of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "List the local branches with their upstream and divergence"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "git_prompt branches | where ahead > 0",
+                description: "Show the branches with unpushed commits",
+                result: None,
+            },
+            Example {
+                example: "git_prompt branches | sort-by age",
+                description: "Show the most recently committed branches first",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+        let branches = repo.branches(Some(BranchType::Local)).map_err(|err| {
+            LabeledError::new("Can't list the branches")
+                .with_label(err.message().to_string(), call.head)
+        })?;
+
+        let span = call.head;
+        let mut rows = Vec::new();
+        for (branch, _) in branches.flatten() {
+            let Ok(Some(name)) = branch.name() else {
+                continue;
+            };
+
+            let local = branch.get().target();
+            let upstream = branch.upstream().ok();
+            let upstream_name = upstream
+                .as_ref()
+                .and_then(|upstream| upstream.name().ok().flatten())
+                .map_or(Value::nothing(span), |name| Value::string(name, span));
+
+            let (ahead, behind) =
+                match (local, upstream.and_then(|upstream| upstream.get().target())) {
+                    (Some(local), Some(upstream)) => {
+                        repo.graph_ahead_behind(local, upstream).map_or(
+                            (Value::nothing(span), Value::nothing(span)),
+                            |(ahead, behind)| {
+                                (
+                                    Value::int(ahead as i64, span),
+                                    Value::int(behind as i64, span),
+                                )
+                            },
+                        )
+                    }
+                    _ => (Value::nothing(span), Value::nothing(span)),
+                };
+
+            let age = branch
+                .get()
+                .peel_to_commit()
+                .map_or(Value::nothing(span), |commit| {
+                    age_value(commit.time().seconds(), span)
+                });
+
+            rows.push(Value::record(
+                record! {
+                    "name" => Value::string(name, span),
+                    "current" => Value::bool(branch.is_head(), span),
+                    "upstream" => upstream_name,
+                    "ahead" => ahead,
+                    "behind" => behind,
+                    "age" => age,
+                },
+                span,
+            ));
+        }
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn lists_the_local_branches_with_their_upstream() {
+        let repo = TempRepo::new();
+        let base = repo.commit_file("a", "a");
+        repo.set_upstream("https://example.com/repo.git", base);
+        repo.commit_file("b", "b");
+        let base_commit = repo.repo.find_commit(base).unwrap();
+        repo.repo.branch("topic", &base_commit, false).unwrap();
+
+        let source = format!("git_prompt branches --path {:?}", repo.path());
+        let branches = eval(&source).unwrap();
+        let row = |name: &str| {
+            branches
+                .as_list()
+                .unwrap()
+                .iter()
+                .map(|row| row.as_record().unwrap().clone())
+                .find(|row| row.get("name") == Some(&Value::test_string(name)))
+                .unwrap()
+        };
+
+        let main = row("main");
+        assert_eq!(main.get("current"), Some(&Value::test_bool(true)));
+        assert_eq!(
+            main.get("upstream"),
+            Some(&Value::test_string("origin/main"))
+        );
+        assert_eq!(main.get("ahead"), Some(&Value::test_int(1)));
+        assert_eq!(main.get("behind"), Some(&Value::test_int(0)));
+
+        let topic = row("topic");
+        assert_eq!(topic.get("current"), Some(&Value::test_bool(false)));
+        assert_eq!(topic.get("upstream"), Some(&Value::test_nothing()));
+        assert_eq!(topic.get("ahead"), Some(&Value::test_nothing()));
+    }
+}
@@ -0,0 +1,121 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::discover_repo;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptCacheClear;
+
+impl SimplePluginCommand for GitPromptCacheClear {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt cache clear"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::Nothing)])
+            .switch(
+                "all",
+                "clear the cache of every repository, not only the current one",
+                Some('a'),
+            )
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to clear the cache of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Forget the cached segments so the next prompt recomputes them"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "git_prompt cache clear",
+                description: "Clear the cache of the current repository",
+                result: None,
+            },
+            Example {
+                example: "git_prompt cache clear --all",
+                description: "Clear the cache of every repository",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        if call.has_flag("all")? {
+            plugin.cache.clear(None);
+            plugin.rows.clear(None);
+            plugin.prompts.clear(None);
+            plugin.statuses.clear(None);
+            return Ok(Value::nothing(call.head));
+        }
+
+        let repo = discover_repo(engine, call)?;
+        plugin.cache.clear(Some(repo.path()));
+        plugin.prompts.clear(Some(repo.path()));
+        plugin.statuses.clear(Some(repo.path()));
+        if let Some(workdir) = repo.workdir() {
+            plugin.rows.clear(Some(workdir.components().as_path()));
+        }
+
+        Ok(Value::nothing(call.head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, eval_with, TempRepo};
+    use crate::GitPromptPlugin;
+
+    #[test]
+    fn the_next_prompt_recomputes_the_segments() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let plugin = Arc::new(GitPromptPlugin::default());
+        let config = "$env.config.plugins.git_prompt = { color: false, status_ttl: 60, segments: [unstaged] }";
+        let prompt = format!("{config}; git_prompt --path {:?}", repo.path());
+        let clear = format!("git_prompt cache clear --path {:?}", repo.path());
+
+        let before = eval_with(&plugin, &prompt).unwrap();
+        // Edits in the working tree don't change the index, so the status
+        // scan is reused until its TTL runs out.
+        repo.write("a", "b");
+        assert_eq!(eval_with(&plugin, &prompt).unwrap(), before);
+
+        assert_eq!(eval_with(&plugin, &clear).unwrap(), Value::test_nothing());
+        assert_ne!(eval_with(&plugin, &prompt).unwrap(), before);
+
+        repo.write("a", "a");
+        let stale = eval_with(&plugin, &prompt).unwrap();
+        eval_with(&plugin, "git_prompt cache clear --all").unwrap();
+        assert_ne!(eval_with(&plugin, &prompt).unwrap(), stale);
+    }
+
+    #[test]
+    fn fails_outside_a_repository() {
+        let repo = TempRepo::new();
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        let source = format!("git_prompt cache clear --path {:?}", repo.path());
+
+        let err = eval(&source).unwrap_err();
+        assert!(err.contains("Not a git repository"), "{err}");
+    }
+}
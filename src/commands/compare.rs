@@ -0,0 +1,124 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Signature, Spanned, SyntaxShape, Type, Value,
+};
+
+use crate::commands::discover_repo;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptCompare;
+
+impl SimplePluginCommand for GitPromptCompare {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt compare"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::record())])
+            .required(
+                "ref",
+                SyntaxShape::String,
+                "branch, tag or commit to compare HEAD with",
+            )
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to compare in instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Commits HEAD is ahead of and behind another ref, and their merge base"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt compare origin/main",
+            description: "Compare HEAD with the main branch of origin",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let other: Spanned<String> = call.req(0)?;
+        let repo = discover_repo(engine, call)?;
+        let span = call.head;
+
+        let head = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|err| {
+                LabeledError::new("HEAD has no commit").with_label(err.message().to_string(), span)
+            })?;
+        let other_commit = repo
+            .revparse_single(&other.item)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|err| {
+                LabeledError::new("Unknown revision")
+                    .with_label(err.message().to_string(), other.span)
+            })?;
+
+        let (ahead, behind) = repo
+            .graph_ahead_behind(head.id(), other_commit.id())
+            .map_err(|err| {
+                LabeledError::new("Can't compare the commits")
+                    .with_label(err.message().to_string(), span)
+            })?;
+        // Unrelated histories have no merge base.
+        let merge_base = repo
+            .merge_base(head.id(), other_commit.id())
+            .map_or(Value::nothing(span), |oid| {
+                Value::string(oid.to_string(), span)
+            });
+
+        Ok(Value::record(
+            record! {
+                "ahead" => Value::int(ahead as i64, span),
+                "behind" => Value::int(behind as i64, span),
+                "merge_base" => merge_base,
+            },
+            span,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn counts_the_commits_on_each_side() {
+        let repo = TempRepo::new();
+        let base = repo.commit_file("a", "a");
+        let base_commit = repo.repo.find_commit(base).unwrap();
+        repo.repo.branch("topic", &base_commit, false).unwrap();
+        repo.commit_file("b", "b");
+        repo.commit_file("c", "c");
+        let path = format!("--path {:?}", repo.path());
+
+        let compared = eval(&format!("git_prompt compare topic {path}")).unwrap();
+        let compared = compared.as_record().unwrap();
+        assert_eq!(compared.get("ahead"), Some(&Value::test_int(2)));
+        assert_eq!(compared.get("behind"), Some(&Value::test_int(0)));
+        assert_eq!(
+            compared.get("merge_base"),
+            Some(&Value::test_string(base.to_string()))
+        );
+
+        let err = eval(&format!("git_prompt compare nowhere {path}")).unwrap_err();
+        assert!(err.contains("Unknown revision"), "{err}");
+    }
+}
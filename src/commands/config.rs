@@ -0,0 +1,168 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, Spanned, SyntaxShape, Type, Value};
+
+use crate::config::{effective, Config};
+use crate::overrides;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptConfigGet;
+
+impl SimplePluginCommand for GitPromptConfigGet {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt config get"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::Any)])
+            .optional(
+                "key",
+                SyntaxShape::String,
+                "dotted path of a setting, e.g. divergence.target",
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Settings in effect: the plugin config with the saved overrides on top"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "git_prompt config get",
+                description: "Show every setting that isn't a default",
+                result: None,
+            },
+            Example {
+                example: "git_prompt config get divergence.target",
+                description: "Show a single setting",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let record = effective(engine)?;
+
+        let Some(key) = call.opt::<Spanned<String>>(0)? else {
+            return Ok(Value::record(record, call.head));
+        };
+
+        Ok(overrides::get(&record, &key.item)
+            .cloned()
+            .map_or(Value::nothing(call.head), |value| {
+                value.with_span(call.head)
+            }))
+    }
+}
+
+pub struct GitPromptConfigSet;
+
+impl SimplePluginCommand for GitPromptConfigSet {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt config set"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::Nothing)])
+            .required(
+                "key",
+                SyntaxShape::String,
+                "dotted path of a setting, e.g. divergence.target",
+            )
+            .required(
+                "value",
+                SyntaxShape::Any,
+                "new value, or null to remove the override",
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Save a setting that overrides the plugin config, kept across restarts"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "git_prompt config set theme ascii",
+                description: "Switch to the ASCII theme",
+                result: None,
+            },
+            Example {
+                example: "git_prompt config set divergence.behind_warning 20",
+                description: "Highlight the behind count from 20 commits",
+                result: None,
+            },
+            Example {
+                example: "git_prompt config set theme null",
+                description: "Remove the theme override",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let key: Spanned<String> = call.req(0)?;
+        let value: Value = call.req(1)?;
+
+        let mut saved = overrides::load(call.head)?;
+        overrides::set(&mut saved, &key.item, value);
+
+        // Nothing invalid is saved, or every following prompt would fail.
+        let mut record = match engine.get_plugin_config()? {
+            Some(value) => value.into_record()?,
+            None => Default::default(),
+        };
+        overrides::merge(&mut record, &saved);
+        Config::from_record(&record)?;
+
+        overrides::save(&saved, call.head)?;
+
+        Ok(Value::nothing(call.head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::eval;
+
+    #[test]
+    fn get_reads_the_plugin_config() {
+        let source = "$env.config.plugins.git_prompt = { divergence: { target: upstream } }; \
+                      git_prompt config get divergence.target";
+        assert_eq!(eval(source).unwrap(), Value::test_string("upstream"));
+
+        let missing = "git_prompt config get divergence.no_such_key";
+        assert_eq!(eval(missing).unwrap(), Value::test_nothing());
+    }
+
+    #[test]
+    fn set_rejects_invalid_settings() {
+        let err = eval("git_prompt config set no_such_key 1").unwrap_err();
+        assert!(err.contains("no_such_key"), "{err}");
+
+        let err = eval("git_prompt config set theme 1").unwrap_err();
+        assert!(err.contains("theme"), "{err}");
+    }
+}
@@ -0,0 +1,123 @@
+use git2::IndexEntry;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Signature, Span, SyntaxShape, Type, Value,
+};
+
+use crate::commands::discover_repo;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptConflicts;
+
+impl SimplePluginCommand for GitPromptConflicts {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt conflicts"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to list the conflicts of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "List the conflicted paths with the object ids of each side"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "^$env.EDITOR ...(git_prompt conflicts | get path)",
+            description: "Open every conflicted file in the editor",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+        let span = call.head;
+
+        let read_error = |err: git2::Error| {
+            LabeledError::new("Can't read the conflicts")
+                .with_label(err.message().to_string(), span)
+        };
+
+        let index = repo.index().map_err(read_error)?;
+        let conflicts = index.conflicts().map_err(read_error)?;
+
+        let rows = conflicts
+            .flatten()
+            .filter_map(|conflict| {
+                let entry = conflict
+                    .our
+                    .as_ref()
+                    .or(conflict.their.as_ref())
+                    .or(conflict.ancestor.as_ref())?;
+                let path = String::from_utf8_lossy(&entry.path).into_owned();
+
+                Some(Value::record(
+                    record! {
+                        "path" => Value::string(path, span),
+                        "ancestor" => oid_value(conflict.ancestor.as_ref(), span),
+                        "ours" => oid_value(conflict.our.as_ref(), span),
+                        "theirs" => oid_value(conflict.their.as_ref(), span),
+                    },
+                    span,
+                ))
+            })
+            .collect();
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+/// Object id of one side of a conflict, `nothing` when that side doesn't
+/// have the file.
+fn oid_value(entry: Option<&IndexEntry>, span: Span) -> Value {
+    entry.map_or(Value::nothing(span), |entry| {
+        Value::string(entry.id.to_string(), span)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{column, eval, TempRepo};
+
+    #[test]
+    fn lists_the_sides_of_each_conflict() {
+        let repo = TempRepo::new();
+        repo.commit_file("init", "init");
+        let source = format!("git_prompt conflicts --path {:?}", repo.path());
+        assert_eq!(eval(&source).unwrap(), Value::test_list(vec![]));
+
+        repo.merge_conflict(&["both"], &["added"]);
+        let conflicts = eval(&source).unwrap();
+        assert_eq!(
+            column(&conflicts, "path"),
+            [Value::test_string("added"), Value::test_string("both")]
+        );
+        // Files added on both sides have no common ancestor.
+        let ancestors = column(&conflicts, "ancestor");
+        assert_eq!(ancestors[0], Value::test_nothing());
+        assert!(ancestors[1].as_str().is_ok());
+        assert_ne!(
+            column(&conflicts, "ours")[1],
+            column(&conflicts, "theirs")[1]
+        );
+    }
+}
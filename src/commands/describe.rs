@@ -0,0 +1,130 @@
+use git2::{DescribeFormatOptions, DescribeOptions};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::discover_repo;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptDescribe;
+
+impl SimplePluginCommand for GitPromptDescribe {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt describe"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![
+                (Type::Nothing, Type::String),
+                (Type::Nothing, Type::Nothing),
+            ])
+            .switch("exact", "only a tag pointing at HEAD itself", Some('e'))
+            .switch(
+                "tags",
+                "use lightweight tags too, not only annotated ones",
+                Some('t'),
+            )
+            .switch(
+                "long",
+                "always add the distance and commit id, even on a tag",
+                Some('l'),
+            )
+            .switch(
+                "dirty",
+                "add -dirty when the working tree has changes",
+                Some('d'),
+            )
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to describe instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Describe HEAD from the most recent tag like `git describe`, nothing when there is no tag"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt describe --tags --long --dirty",
+            description: "Get a version string such as v1.2.3-4-gabc1234-dirty",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+
+        let mut options = DescribeOptions::new();
+        if call.has_flag("tags")? {
+            options.describe_tags();
+        }
+
+        if call.has_flag("exact")? {
+            options.max_candidates_tags(0);
+        }
+
+        let mut format = DescribeFormatOptions::new();
+        format.abbreviated_size(7);
+        if call.has_flag("long")? {
+            format.always_use_long_format(true);
+        }
+
+        if call.has_flag("dirty")? {
+            format.dirty_suffix("-dirty");
+        }
+
+        // No tag to describe from isn't an error for a prompt.
+        let described = repo
+            .describe(&options)
+            .and_then(|describe| describe.format(Some(&format)));
+
+        Ok(match described {
+            Ok(described) => Value::string(described, call.head),
+            Err(_) => Value::nothing(call.head),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn describes_like_git_describe() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.tag("v1.0.0", true);
+        repo.tag("light", false);
+        let path = format!("--path {:?}", repo.path());
+
+        let exact = eval(&format!("git_prompt describe --exact {path}")).unwrap();
+        assert_eq!(exact.as_str().unwrap(), "v1.0.0");
+
+        let head = repo.commit_file("b", "b").to_string();
+        let described = eval(&format!("git_prompt describe {path}")).unwrap();
+        assert_eq!(
+            described.as_str().unwrap(),
+            format!("v1.0.0-1-g{}", &head[..7])
+        );
+        let exact = eval(&format!("git_prompt describe --exact {path}")).unwrap();
+        assert_eq!(exact, Value::test_nothing());
+
+        repo.write("a", "edited");
+        let dirty = eval(&format!("git_prompt describe --dirty {path}")).unwrap();
+        assert!(dirty.as_str().unwrap().ends_with("-dirty"));
+    }
+}
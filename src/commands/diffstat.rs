@@ -0,0 +1,149 @@
+use git2::{Diff, Patch};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Signature, Span, SyntaxShape, Type, Value,
+};
+
+use crate::commands::discover_repo;
+use crate::diff;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptDiffstat;
+
+impl SimplePluginCommand for GitPromptDiffstat {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt diffstat"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to show the changes of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Inserted and deleted lines per file, for staged and unstaged changes"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt diffstat | where area == staged | get insertions | math sum",
+            description: "Count the staged insertions",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+        let span = call.head;
+
+        let diff_error = |err: git2::Error| {
+            LabeledError::new("Can't compute the diff").with_label(err.message().to_string(), span)
+        };
+
+        let mut rows = Vec::new();
+        let staged = diff::staged(&repo).map_err(diff_error)?;
+        push_rows(&mut rows, &staged, "staged", span).map_err(diff_error)?;
+        let unstaged = diff::unstaged(&repo).map_err(diff_error)?;
+        push_rows(&mut rows, &unstaged, "unstaged", span).map_err(diff_error)?;
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+/// Adds a row per changed file of `diff`.
+fn push_rows(
+    rows: &mut Vec<Value>,
+    diff: &Diff,
+    area: &str,
+    span: Span,
+) -> Result<(), git2::Error> {
+    for index in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, index)? else {
+            continue;
+        };
+
+        let delta = patch.delta();
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let (_, insertions, deletions) = patch.line_stats()?;
+
+        rows.push(Value::record(
+            record! {
+                "path" => Value::string(path, span),
+                "area" => Value::string(area, span),
+                "insertions" => Value::int(insertions as i64, span),
+                "deletions" => Value::int(deletions as i64, span),
+            },
+            span,
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn counts_the_lines_of_each_area() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "one\ntwo\n");
+        repo.write("a", "one\n2\nthree\n");
+        repo.stage("a");
+        repo.write("a", "one\n");
+
+        let source = format!("git_prompt diffstat --path {:?}", repo.path());
+        let diffstat = eval(&source).unwrap();
+        let rows: Vec<(Value, Value, Value)> = diffstat
+            .as_list()
+            .unwrap()
+            .iter()
+            .map(|row| {
+                let row = row.as_record().unwrap();
+                (
+                    row.get("area").unwrap().clone(),
+                    row.get("insertions").unwrap().clone(),
+                    row.get("deletions").unwrap().clone(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            rows,
+            [
+                (
+                    Value::test_string("staged"),
+                    Value::test_int(2),
+                    Value::test_int(1)
+                ),
+                (
+                    Value::test_string("unstaged"),
+                    Value::test_int(0),
+                    Value::test_int(2)
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use git2::StatusOptions;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::target_dir;
+use crate::config::GitEnv;
+use crate::status::Untracked;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptDirty;
+
+impl SimplePluginCommand for GitPromptDirty {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt dirty"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::Bool)])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "directory to check instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Whether the working tree or the index has changes, false outside a git repository"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "if (git_prompt dirty) { '●' } else { '' }",
+            description: "Show a dot when there are uncommitted changes",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let Some(dir) = target_dir(engine, call)? else {
+            return Ok(Value::bool(false, call.head));
+        };
+
+        let Ok(repo) = GitEnv::load(engine)?.discover(&dir) else {
+            return Ok(Value::bool(false, call.head));
+        };
+
+        // The cheapest scan that still notices any change: untracked
+        // directories aren't walked into and renames aren't detected.
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(Untracked::of(&repo) != Untracked::No)
+            .recurse_untracked_dirs(false)
+            .include_ignored(false)
+            .exclude_submodules(true);
+
+        let dirty = repo
+            .statuses(Some(&mut options))
+            .is_ok_and(|statuses| !statuses.is_empty());
+
+        Ok(Value::bool(dirty, call.head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn notices_any_change() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let source = format!("git_prompt dirty --path {:?}", repo.path());
+        assert_eq!(eval(&source).unwrap(), Value::test_bool(false));
+
+        repo.write("dir/new", "new");
+        assert_eq!(eval(&source).unwrap(), Value::test_bool(true));
+
+        repo.repo
+            .config()
+            .unwrap()
+            .set_str("status.showUntrackedFiles", "no")
+            .unwrap();
+        assert_eq!(eval(&source).unwrap(), Value::test_bool(false));
+
+        repo.write("a", "edited");
+        assert_eq!(eval(&source).unwrap(), Value::test_bool(true));
+    }
+}
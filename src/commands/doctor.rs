@@ -0,0 +1,288 @@
+use git2::ErrorCode;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Signature, Span, SyntaxShape, Type, Value,
+};
+
+use crate::commands::git_prompt::git_dir_size;
+use crate::commands::target_dir;
+use crate::config::{opt_out, Config};
+use crate::context::Context;
+use crate::status::status_options;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptDoctor;
+
+impl SimplePluginCommand for GitPromptDoctor {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt doctor"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "directory to diagnose instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Run the checks of the prompt one by one to find out why it is empty"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt doctor | where not ok",
+            description: "Show the checks that failed",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let mut report = Report {
+            rows: Vec::new(),
+            span: call.head,
+        };
+
+        let Some(dir) = target_dir(engine, call)? else {
+            report.push("directory", false, "the current directory is unknown");
+            return Ok(report.into_value());
+        };
+        let base = Config::load(engine)?;
+        let config = Config::load_for(engine, Some(&dir))?;
+
+        if !dir.is_dir() {
+            report.push(
+                "directory",
+                false,
+                format!("{} doesn't exist", dir.display()),
+            );
+            return Ok(report.into_value());
+        }
+        report.push("directory", true, dir.display().to_string());
+
+        if base.allows_path(&dir) {
+            report.push(
+                "paths",
+                true,
+                "not excluded by enabled_paths or disabled_paths",
+            );
+        } else if base.enabled_paths.is_empty() {
+            report.push("paths", false, "matched by disabled_paths");
+        } else {
+            report.push("paths", false, "not matched by enabled_paths");
+        }
+
+        let repo = match config.git_env.discover(&dir) {
+            Ok(repo) => repo,
+            Err(err) if err.code() == ErrorCode::Owner => {
+                report.push(
+                    "repository",
+                    false,
+                    "owned by another user, add the path to trusted_paths to show it",
+                );
+                return Ok(report.into_value());
+            }
+            Err(err) => {
+                report.push("repository", false, err.message().to_string());
+                return Ok(report.into_value());
+            }
+        };
+        report.push(
+            "repository",
+            true,
+            repo.path().components().as_path().display().to_string(),
+        );
+
+        match opt_out(&repo) {
+            Some(reason) => report.push("opt-out", false, reason),
+            None => report.push("opt-out", true, "the repository didn't opt out"),
+        }
+
+        // `load_for` sets `disabled` for the checks above too, so it's only
+        // blamed on the setting when none of them failed.
+        if base.disabled || (config.disabled && base.allows_path(&dir) && opt_out(&repo).is_none())
+        {
+            report.push("enabled", false, "disabled is set in the config");
+        } else {
+            report.push("enabled", true, "disabled isn't set");
+        }
+
+        // Like the prompt, the walk stops once it's over the limit.
+        match config.max_repo_size {
+            Some(limit) => {
+                let size = git_dir_size(repo.commondir(), limit);
+                let detail = if size > limit {
+                    format!(
+                        "over the {} allowed, see max_repo_size",
+                        format_bytes(limit)
+                    )
+                } else {
+                    format!(
+                        "{} of {} allowed, see max_repo_size",
+                        format_bytes(size),
+                        format_bytes(limit)
+                    )
+                };
+                report.push("size", size <= limit, detail);
+            }
+            None => {
+                let size = git_dir_size(repo.commondir(), u64::MAX);
+                report.push("size", true, format!("{}, no limit", format_bytes(size)));
+            }
+        }
+
+        match repo.head() {
+            Ok(head) => report.push("head", true, head.shorthand().unwrap_or("HEAD").to_string()),
+            Err(err) if err.code() == ErrorCode::UnbornBranch => {
+                report.push("head", true, "no commits yet")
+            }
+            Err(err) => report.push("head", false, err.message().to_string()),
+        }
+
+        let ctx = Context::new(&repo, &dir);
+        if ctx.index_locked() {
+            report.push(
+                "index",
+                false,
+                "index.lock exists, another git process is running",
+            );
+        } else {
+            report.push("index", true, "not locked");
+        }
+
+        match repo.statuses(Some(&mut status_options(&repo))) {
+            Ok(statuses) => report.push("status", true, format!("{} entries", statuses.len())),
+            Err(err) => report.push("status", false, err.message().to_string()),
+        }
+
+        let head = ctx.head();
+        if head.upstream_gone {
+            report.push("upstream", false, "the upstream branch no longer exists");
+        } else if head.remote.is_empty() {
+            report.push(
+                "upstream",
+                false,
+                "no upstream, ahead and behind aren't shown",
+            );
+        } else {
+            report.push("upstream", true, head.remote.clone());
+        }
+
+        Ok(report.into_value())
+    }
+}
+
+/// Rows of the doctor table.
+struct Report {
+    rows: Vec<Value>,
+    span: Span,
+}
+
+impl Report {
+    fn push(&mut self, check: &str, ok: bool, detail: impl Into<String>) {
+        self.rows.push(Value::record(
+            record! {
+                "check" => Value::string(check, self.span),
+                "ok" => Value::bool(ok, self.span),
+                "detail" => Value::string(detail, self.span),
+            },
+            self.span,
+        ));
+    }
+
+    fn into_value(self) -> Value {
+        Value::list(self.rows, self.span)
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{bytes} B"),
+        1_000..1_000_000 => format!("{:.1} kB", bytes as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1} MB", bytes as f64 / 1e6),
+        _ => format!("{:.1} GB", bytes as f64 / 1e9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::config::OPT_OUT_FILE;
+    use crate::testing::{eval, TempRepo};
+
+    /// Whether each check passed, by name.
+    fn checks(source: &str) -> Vec<(String, bool)> {
+        eval(source)
+            .unwrap()
+            .as_list()
+            .unwrap()
+            .iter()
+            .map(|row| {
+                let row = row.as_record().unwrap();
+                (
+                    row.get("check").unwrap().as_str().unwrap().to_string(),
+                    row.get("ok").unwrap().as_bool().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn ok(checks: &[(String, bool)], check: &str) -> Option<bool> {
+        checks
+            .iter()
+            .find(|(name, _)| name == check)
+            .map(|(_, ok)| *ok)
+    }
+
+    #[test]
+    fn explains_why_the_prompt_is_hidden() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let source = format!("git_prompt doctor --path {:?}", repo.path());
+
+        let report = checks(&source);
+        for check in [
+            "directory",
+            "paths",
+            "repository",
+            "opt-out",
+            "enabled",
+            "head",
+        ] {
+            assert_eq!(ok(&report, check), Some(true), "{check}");
+        }
+        assert_eq!(ok(&report, "upstream"), Some(false));
+
+        repo.write(OPT_OUT_FILE, "");
+        repo.write(".git/index.lock", "");
+        let report = checks(&source);
+        assert_eq!(ok(&report, "opt-out"), Some(false));
+        assert_eq!(ok(&report, "enabled"), Some(true));
+        assert_eq!(ok(&report, "index"), Some(false));
+    }
+
+    #[test]
+    fn stops_at_a_missing_directory() {
+        let repo = TempRepo::new();
+        let source = format!("git_prompt doctor --path {:?}", repo.path().join("missing"));
+
+        let report = eval(&source).unwrap();
+        assert_eq!(report.as_list().unwrap().len(), 1);
+        let row = report.as_list().unwrap()[0].as_record().unwrap().clone();
+        assert_eq!(row.get("check"), Some(&Value::test_string("directory")));
+    }
+}
@@ -0,0 +1,217 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use git2::{Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::cache::SegmentCache;
+use crate::commands::{discover_repo, target_dir};
+use crate::config::Config;
+use crate::context::Context;
+use crate::repo;
+use crate::segments::Registry;
+use crate::GitPromptPlugin;
+
+/// Times the credentials callback is asked before giving up, so a rejected
+/// credential doesn't make libgit2 retry forever.
+const CREDENTIAL_ATTEMPTS: usize = 3;
+
+/// Segments whose output depends on the remote-tracking refs.
+const REMOTE_SEGMENTS: &[&str] = &[
+    "divergence",
+    "remote",
+    "unpushed",
+    "unpublished",
+    "default_branch",
+];
+
+pub struct GitPromptFetch;
+
+impl SimplePluginCommand for GitPromptFetch {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt fetch"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::Nothing)])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to fetch instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Fetch the remote of the current branch in the background and refresh the cached ahead/behind counts"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt fetch",
+            description: "Fetch the remote of the current branch without waiting for it",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+        let config = Config::load_for(engine, target_dir(engine, call)?.as_deref())?;
+
+        let remote = fetch_remote(&repo).ok_or_else(|| {
+            LabeledError::new("No remote to fetch").with_label(
+                "the current branch has no upstream and there's no origin",
+                call.head,
+            )
+        })?;
+
+        let git_dir = repo.path().to_path_buf();
+        let workdir = repo
+            .workdir()
+            .map_or_else(|| git_dir.clone(), PathBuf::from);
+        let cache = Arc::clone(&plugin.cache);
+        let prompts = Arc::clone(&plugin.prompts);
+
+        // Nothing waits for the thread, so errors while fetching are dropped
+        // and the prompt keeps showing the counts of the last fetch.
+        thread::spawn(move || {
+            let Ok(repo) = repo::open(&git_dir) else {
+                return;
+            };
+
+            if fetch(&repo, &remote).is_ok() {
+                refresh(&repo, &workdir, &config, &cache);
+                // A prompt kept for the `cached` strategy would still show the
+                // counts from before the fetch.
+                prompts.clear(Some(&git_dir));
+            }
+        });
+
+        Ok(Value::nothing(call.head))
+    }
+}
+
+/// Remote of the upstream of the current branch, falling back to `origin`.
+fn fetch_remote(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if let Some(refname) = head.name() {
+        if let Ok(remote) = repo.branch_upstream_remote(refname) {
+            if let Some(remote) = remote.as_str() {
+                return Some(remote.to_string());
+            }
+        }
+    }
+
+    repo.find_remote("origin")
+        .ok()
+        .map(|_| "origin".to_string())
+}
+
+fn fetch(repo: &Repository, name: &str) -> Result<(), git2::Error> {
+    let config = repo.config()?;
+    let mut attempts = 0;
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(|url, username, allowed| {
+        attempts += 1;
+        if attempts > CREDENTIAL_ATTEMPTS {
+            return Err(git2::Error::from_str("no credentials were accepted"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            Cred::ssh_key_from_agent(username.unwrap_or("git"))
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            Cred::credential_helper(&config, url, username)
+        } else if allowed.contains(CredentialType::USERNAME) {
+            Cred::username(username.unwrap_or("git"))
+        } else {
+            Cred::default()
+        }
+    });
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+
+    let mut remote = repo.find_remote(name)?;
+    remote.fetch::<&str>(&[], Some(&mut options), None)
+}
+
+/// Renders the configured segments that compare against remote refs into
+/// `cache`, so the next prompt shows the counts against the fetched refs
+/// without computing them itself.
+fn refresh(repo: &Repository, workdir: &Path, config: &Config, cache: &SegmentCache) {
+    let names: Vec<String> = config
+        .segments
+        .iter()
+        .chain(&config.right_segments)
+        .filter(|name| REMOTE_SEGMENTS.contains(&name.as_str()))
+        .cloned()
+        .collect();
+
+    let ctx = Context::new(repo, workdir);
+    Registry::new(config, &names).render(&ctx, cache);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn fetches_the_upstream_remote_or_origin() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        assert_eq!(fetch_remote(&repo.repo), None);
+
+        repo.repo
+            .remote("origin", "https://example.com/a.git")
+            .unwrap();
+        assert_eq!(fetch_remote(&repo.repo).as_deref(), Some("origin"));
+
+        repo.repo
+            .remote("fork", "https://example.com/b.git")
+            .unwrap();
+        let mut config = repo.repo.config().unwrap();
+        config.set_str("branch.main.remote", "fork").unwrap();
+        config
+            .set_str("branch.main.merge", "refs/heads/main")
+            .unwrap();
+        assert_eq!(fetch_remote(&repo.repo).as_deref(), Some("fork"));
+    }
+
+    #[test]
+    fn fetches_the_remote_refs() {
+        let upstream = TempRepo::new();
+        let oid = upstream.commit_file("a", "a");
+        let repo = TempRepo::new();
+        repo.repo
+            .remote("origin", upstream.path().to_str().unwrap())
+            .unwrap();
+
+        fetch(&repo.repo, "origin").unwrap();
+        let fetched = repo.repo.refname_to_id("refs/remotes/origin/main").unwrap();
+        assert_eq!(fetched, oid);
+    }
+
+    #[test]
+    fn fails_without_a_remote() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let source = format!("git_prompt fetch --path {:?}", repo.path());
+
+        let err = eval(&source).unwrap_err();
+        assert!(err.contains("No remote to fetch"), "{err}");
+    }
+}
@@ -0,0 +1,203 @@
+use git2::Status;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{record, Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::discover_repo;
+use crate::status::status_options;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptFiles;
+
+impl SimplePluginCommand for GitPromptFiles {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt files"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to list the files of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "List the changed files with their index and working tree status"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt files | where worktree_status == modified | get path",
+            description: "Get the files with unstaged modifications",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+        let statuses = repo
+            .statuses(Some(&mut status_options(&repo)))
+            .map_err(|err| {
+                LabeledError::new("Can't read the status")
+                    .with_label(err.message().to_string(), call.head)
+            })?;
+
+        let span = call.head;
+        let text = |value: Option<&str>| {
+            value.map_or(Value::nothing(span), |value| Value::string(value, span))
+        };
+
+        let rows = statuses
+            .iter()
+            .map(|entry| {
+                let status = entry.status();
+                // The entry path of a rename is the old one. A file renamed
+                // in the index and again in the working tree goes from the
+                // name in HEAD to the one in the working tree.
+                let staged = entry
+                    .head_to_index()
+                    .filter(|_| status.contains(Status::INDEX_RENAMED));
+                let unstaged = entry
+                    .index_to_workdir()
+                    .filter(|_| status.contains(Status::WT_RENAMED));
+                let path = unstaged
+                    .as_ref()
+                    .or(staged.as_ref())
+                    .and_then(|delta| delta.new_file().path())
+                    .map(|path| path.display().to_string())
+                    .or_else(|| entry.path().map(str::to_string));
+                let renamed_from = staged
+                    .as_ref()
+                    .or(unstaged.as_ref())
+                    .and_then(|delta| delta.old_file().path())
+                    .map(|path| path.display().to_string());
+
+                Value::record(
+                    record! {
+                        "path" => text(path.as_deref()),
+                        "index_status" => text(index_status(status)),
+                        "worktree_status" => text(worktree_status(status)),
+                        "renamed_from" => text(renamed_from.as_deref()),
+                    },
+                    span,
+                )
+            })
+            .collect();
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+fn index_status(status: Status) -> Option<&'static str> {
+    if status.contains(Status::CONFLICTED) {
+        Some("conflicted")
+    } else if status.contains(Status::INDEX_NEW) {
+        Some("added")
+    } else if status.contains(Status::INDEX_MODIFIED) {
+        Some("modified")
+    } else if status.contains(Status::INDEX_DELETED) {
+        Some("deleted")
+    } else if status.contains(Status::INDEX_RENAMED) {
+        Some("renamed")
+    } else if status.contains(Status::INDEX_TYPECHANGE) {
+        Some("typechange")
+    } else {
+        None
+    }
+}
+
+fn worktree_status(status: Status) -> Option<&'static str> {
+    if status.contains(Status::CONFLICTED) {
+        Some("conflicted")
+    } else if status.contains(Status::WT_NEW) {
+        Some("untracked")
+    } else if status.contains(Status::WT_MODIFIED) {
+        Some("modified")
+    } else if status.contains(Status::WT_DELETED) {
+        Some("deleted")
+    } else if status.contains(Status::WT_RENAMED) {
+        Some("renamed")
+    } else if status.contains(Status::WT_TYPECHANGE) {
+        Some("typechange")
+    } else if status.contains(Status::IGNORED) {
+        Some("ignored")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::{Record, Value};
+
+    use crate::testing::{eval, TempRepo};
+
+    fn rows(value: &Value) -> Vec<Record> {
+        let mut rows: Vec<Record> = value
+            .as_list()
+            .unwrap()
+            .iter()
+            .map(|row| row.as_record().unwrap().clone())
+            .collect();
+        rows.sort_by_key(|row| row.get("path").unwrap().as_str().unwrap().to_string());
+        rows
+    }
+
+    #[test]
+    fn lists_the_changed_files_with_both_statuses() {
+        let repo = TempRepo::new();
+        repo.commit_file("edited", "a");
+        repo.commit_file("old", "contents of a renamed file");
+        repo.write("added", "added");
+        repo.stage("added");
+        repo.write("added", "added and edited");
+        std::fs::rename(repo.path().join("old"), repo.path().join("new")).unwrap();
+        repo.write("edited", "edited");
+
+        let source = format!("git_prompt files --path {:?}", repo.path());
+        let files = rows(&eval(&source).unwrap());
+        let summary: Vec<(&str, Value, Value)> = files
+            .iter()
+            .map(|row| {
+                (
+                    row.get("path").unwrap().as_str().unwrap(),
+                    row.get("index_status").unwrap().clone(),
+                    row.get("worktree_status").unwrap().clone(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                (
+                    "added",
+                    Value::test_string("added"),
+                    Value::test_string("modified")
+                ),
+                (
+                    "edited",
+                    Value::test_nothing(),
+                    Value::test_string("modified")
+                ),
+                ("new", Value::test_nothing(), Value::test_string("renamed")),
+            ]
+        );
+        assert_eq!(
+            files[2].get("renamed_from"),
+            Some(&Value::test_string("old"))
+        );
+    }
+}
@@ -0,0 +1,522 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, Signature, Span, Spanned, SyntaxShape, Type, Value,
+};
+
+use git2::{ErrorCode, Repository};
+use walkdir::WalkDir;
+
+use crate::cache::StatusCache;
+use crate::commands::target_dir;
+use crate::config::{opt_out, Config, Degrade};
+use crate::context::Context;
+use crate::format::Format;
+use crate::record::status_record;
+use crate::repo;
+use crate::segments::{self, Registry, Segment};
+use crate::status::GitStatus;
+use crate::theme::{self, THEMES};
+use crate::GitPromptPlugin;
+
+pub struct GitPrompt;
+
+impl SimplePluginCommand for GitPrompt {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![
+                (Type::Nothing, Type::String),
+                (Type::Nothing, Type::record()),
+            ])
+            .named(
+                "theme",
+                SyntaxShape::String,
+                format!("symbol theme to use: {}", THEMES.join(", ")),
+                Some('t'),
+            )
+            .named(
+                "format",
+                SyntaxShape::String,
+                "starship-style format string, e.g. '[$branch](bold) $all_status'",
+                Some('f'),
+            )
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "directory to show the status of instead of the current one",
+                Some('p'),
+            )
+            .switch(
+                "no-color",
+                "leave out the styles of the format string",
+                None,
+            )
+            .switch(
+                "record",
+                "return the status record instead of the prompt string",
+                Some('r'),
+            )
+            .switch(
+                "nothing",
+                "return nothing instead of an empty string outside a git repository",
+                None,
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "One line git status output to show in your nushell prompt"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "git_prompt",
+                description: "Show the status of the repository in the current directory",
+                result: None,
+            },
+            Example {
+                example: "git_prompt --theme ascii",
+                description: "Use plain ASCII symbols",
+                result: None,
+            },
+            Example {
+                example: "git_prompt --format '[$branch](bold purple) $all_status$ahead_behind'",
+                description: "Render with a starship-style format string",
+                result: None,
+            },
+            Example {
+                example: "git_prompt --record",
+                description: "Get the status as a record",
+                result: None,
+            },
+            Example {
+                example:
+                    "ls ~/src | where type == dir | each { git_prompt --record --path $in.name }",
+                description: "Get the status of every repository in a directory",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let (mut config, target) = load(engine, target_dir(engine, call)?)?;
+
+        if let Some(name) = call.get_flag::<Spanned<String>>("theme")? {
+            theme::apply(&mut config, &name.item)
+                .map_err(|msg| LabeledError::new("Invalid theme").with_label(msg, name.span))?;
+        }
+
+        if let Some(format) = call.get_flag::<Spanned<String>>("format")? {
+            config.format = Some(Format::parse(&format.item).map_err(|msg| {
+                LabeledError::new("Invalid git_prompt format").with_label(msg, format.span)
+            })?);
+        }
+
+        if call.has_flag("no-color")? {
+            config.color = false;
+        }
+
+        let as_record = call.has_flag("record")?;
+        let record_span = (as_record || config.renderer.is_some()).then_some(call.head);
+        let names = match &config.format {
+            Some(format) => format.segments(),
+            None => config.segments.clone(),
+        };
+        let (v, record) =
+            match render_segments(plugin, target.as_ref(), &config, &names, record_span) {
+                Render::Segments { segments, record } => (segments, record),
+                Render::NotRepo if config.nothing_outside_repo || call.has_flag("nothing")? => {
+                    return Ok(Value::nothing(call.head));
+                }
+                Render::UnsafeOwner if !as_record => {
+                    return Ok(Value::string(config.unsafe_owner.clone(), call.head));
+                }
+                Render::Broken if !as_record => {
+                    return Ok(Value::string(config.broken.clone(), call.head));
+                }
+                Render::UnsafeOwner | Render::Broken => return Ok(Value::nothing(call.head)),
+                Render::NotRepo | Render::Hidden => return Ok(Value::string("", call.head)),
+            };
+
+        if as_record {
+            return Ok(record.unwrap_or_else(|| Value::nothing(call.head)));
+        }
+
+        if let (Some(renderer), Some(record)) = (&config.renderer, record) {
+            return Ok(engine.eval_closure(renderer, vec![record], None)?);
+        }
+
+        if let Some(format) = &config.format {
+            return Ok(Value::string(format.render(&v, config.color), call.head));
+        }
+
+        let formatted = format!(" {}", segments::join(&v).trim());
+        Ok(Value::string(formatted, call.head))
+    }
+}
+
+/// Outcome of rendering the prompt segments.
+pub enum Render {
+    /// The current directory isn't inside a git repository.
+    NotRepo,
+    /// Nothing is shown, because the prompt is disabled or the repository
+    /// is over the size threshold.
+    Hidden,
+    /// The repository opened but reading its references or status failed,
+    /// e.g. because of a corrupt index.
+    Broken,
+    /// The repository is owned by another user and its path isn't trusted,
+    /// so libgit2 refuses to open it.
+    UnsafeOwner,
+    Segments {
+        segments: Vec<Segment>,
+        /// The status record, when it was asked for.
+        record: Option<Value>,
+    },
+}
+
+/// Directory the prompt is rendered for, with the result of opening its
+/// repository.
+pub struct Target {
+    pub dir: PathBuf,
+    pub repo: Result<Repository, git2::Error>,
+}
+
+/// Loads the config for `dir` along with its repository, so the repository is
+/// discovered once for both. No repository is opened for a directory that
+/// doesn't exist or that `enabled_paths` and `disabled_paths` exclude.
+pub fn load(
+    engine: &EngineInterface,
+    dir: Option<PathBuf>,
+) -> Result<(Config, Option<Target>), LabeledError> {
+    let mut config = Config::load(engine)?;
+    let Some(dir) = dir else {
+        return Ok((config, None));
+    };
+
+    if !config.allows_path(&dir) {
+        config.disabled = true;
+        return Ok((config, None));
+    }
+
+    if !dir.is_dir() {
+        return Ok((config, None));
+    }
+
+    let repo = open_repository(&dir, &config);
+    if let Ok(repo) = &repo {
+        config.apply_repo(engine, repo)?;
+    }
+
+    Ok((config, Some(Target { dir, repo })))
+}
+
+/// Renders the given segments for `target`, along with the status record
+/// when `record_span` is set.
+pub fn render_segments(
+    plugin: &GitPromptPlugin,
+    target: Option<&Target>,
+    config: &Config,
+    segments: &[String],
+    record_span: Option<Span>,
+) -> Render {
+    if config.disabled {
+        return Render::Hidden;
+    }
+
+    let Some(target) = target else {
+        return Render::NotRepo;
+    };
+
+    let repo = match &target.repo {
+        Ok(repo) => repo,
+        Err(err) if err.code() == ErrorCode::Owner => {
+            return Render::UnsafeOwner;
+        }
+        Err(_) => {
+            return Render::NotRepo;
+        }
+    };
+
+    // The objects of a linked worktree are in the common dir, so that's the
+    // one measured.
+    let oversized = config
+        .max_repo_size
+        .is_some_and(|limit| git_dir_size(repo.commondir(), limit) > limit);
+    if oversized && config.degrade == Degrade::Blank {
+        return Render::Hidden;
+    }
+
+    if let Err(err) = repo.head() {
+        if !matches!(err.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) {
+            return Render::Broken;
+        }
+    }
+
+    let ctx = Context::new(repo, &target.dir);
+
+    if oversized {
+        return Render::Segments {
+            segments: degraded(plugin, &ctx, config, segments),
+            record: None,
+        };
+    }
+
+    let status_key = ctx.status_key();
+    let mut cached_status = plugin
+        .statuses
+        .get(repo.path(), &status_key, config.status_ttl);
+    let mut stale = false;
+    if cached_status.is_none() && config.background_status {
+        cached_status = plugin.statuses.last(repo.path());
+        if cached_status.is_some() {
+            refresh_status(&plugin.statuses, repo, status_key.clone());
+            stale = true;
+        }
+    }
+    let rescanned = cached_status.is_none();
+    if let Some(status) = cached_status {
+        ctx.set_status(status);
+    }
+
+    let mut v = Registry::new(config, segments).render(&ctx, &plugin.cache);
+
+    // The status scan is only run when a segment asks for it, but when it
+    // fails the counts can't be trusted, so only a warning is shown. A locked
+    // index explains the failure, so the rest of the prompt is kept along
+    // with the lock segment.
+    if ctx.status_failed() && !ctx.index_locked() {
+        return Render::Broken;
+    }
+
+    let record = record_span.map(|span| status_record(&ctx, &v, span));
+    if let (true, Some(status)) = (rescanned, ctx.scanned_status()) {
+        plugin
+            .statuses
+            .insert(repo.path(), status_key, status.clone());
+    }
+
+    // The marker isn't cached, `degraded` adds its own to a cached prompt.
+    plugin.prompts.insert(repo.path(), segments, v.clone());
+    if stale {
+        v.push(Segment {
+            name: "stale",
+            text: config.stale.clone(),
+        });
+    }
+
+    Render::Segments {
+        segments: v,
+        record,
+    }
+}
+
+/// Segments of a repository over `max_repo_size`, according to `degrade`.
+/// Only the cheap segments among `segments` are rendered, and the status
+/// record is left out since it needs the status scan.
+fn degraded(
+    plugin: &GitPromptPlugin,
+    ctx: &Context,
+    config: &Config,
+    segments: &[String],
+) -> Vec<Segment> {
+    if config.degrade == Degrade::Cached {
+        if let Some(mut cached) = plugin.prompts.get(ctx.repo.path(), segments) {
+            cached.push(Segment {
+                name: "stale",
+                text: config.stale.clone(),
+            });
+            return cached;
+        }
+    }
+
+    // Without a cached prompt, `cached` falls back to the branch alone.
+    let cheap: &[&str] = match config.degrade {
+        Degrade::BranchState => &["branch", "state"],
+        _ => &["branch"],
+    };
+    let names: Vec<String> = segments
+        .iter()
+        .filter(|name| cheap.contains(&name.as_str()))
+        .cloned()
+        .collect();
+
+    Registry::new(config, &names).render(ctx, &plugin.cache)
+}
+
+/// Scans the status of `repo` again on a thread of its own and stores it in
+/// `statuses` with `key`, unless a scan of it is already running.
+fn refresh_status(statuses: &Arc<StatusCache>, repo: &Repository, key: String) {
+    let git_dir = repo.path().to_path_buf();
+    if !statuses.start_refresh(&git_dir) {
+        return;
+    }
+
+    let workdir = repo.workdir().map(Path::to_path_buf);
+    let statuses = Arc::clone(statuses);
+    thread::spawn(move || {
+        let status = repo::open(&git_dir).ok().and_then(|repo| {
+            if let Some(workdir) = &workdir {
+                repo.set_workdir(workdir, false).ok()?;
+            }
+            GitStatus::init(&repo)
+        });
+
+        if let Some(status) = status {
+            statuses.insert(&git_dir, key, status);
+        }
+        statuses.finish_refresh(&git_dir);
+    });
+}
+
+/// Opens the repository containing `path`, or the one of `GIT_DIR`,
+/// skipping libgit2's ownership check when the path is under one of the
+/// configured `trusted_paths`.
+fn open_repository(path: &Path, config: &Config) -> Result<Repository, git2::Error> {
+    match config.git_env.discover(path) {
+        Err(err) if err.code() == ErrorCode::Owner && is_trusted(path, config) => {
+            config.git_env.discover_trusted(path)
+        }
+        result => result,
+    }
+}
+
+/// Opens the working tree at `path`, found by walking a directory, with the
+/// checks the prompt makes: `None` when `enabled_paths` and `disabled_paths`
+/// exclude it, it opted out, or its git dir is over `max_repo_size`. Unlike
+/// `open_repository`, `GIT_DIR` isn't followed since it would name the same
+/// repository for every path.
+pub fn open_found(path: &Path, config: &Config) -> Option<Repository> {
+    if !config.allows_path(path) {
+        return None;
+    }
+
+    let repo = match repo::open(path) {
+        Err(err) if err.code() == ErrorCode::Owner && is_trusted(path, config) => {
+            repo::unchecked(|| Repository::open(path))
+        }
+        result => result,
+    }
+    .ok()?;
+
+    if opt_out(&repo).is_some() {
+        return None;
+    }
+
+    let oversized = config
+        .max_repo_size
+        .is_some_and(|limit| git_dir_size(repo.commondir(), limit) > limit);
+
+    (!oversized).then_some(repo)
+}
+
+fn is_trusted(path: &Path, config: &Config) -> bool {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    config.trusted_paths.iter().any(|trusted| {
+        let trusted = trusted.canonicalize().unwrap_or_else(|_| trusted.clone());
+        path.starts_with(trusted)
+    })
+}
+
+/// Total size of the files in `git_dir`, counted until it goes over `limit`.
+pub fn git_dir_size(git_dir: &Path, limit: u64) -> u64 {
+    let mut size: u64 = 0;
+    for entry in WalkDir::new(git_dir).into_iter().flatten() {
+        if let Ok(metadata) = entry.metadata() {
+            if metadata.is_file() {
+                size += metadata.len();
+                if size > limit {
+                    break;
+                }
+            }
+        }
+    }
+
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{eval, record, TempRepo};
+
+    #[test]
+    fn open_found_makes_the_prompt_checks() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let config = Config::default();
+        assert!(open_found(repo.path(), &config).is_some());
+
+        let glob = format!("disabled_paths = [{:?}]", repo.path());
+        let mut disabled = Config::default();
+        disabled.update(record(&glob).as_record().unwrap()).unwrap();
+        assert!(open_found(repo.path(), &disabled).is_none());
+
+        let small = Config {
+            max_repo_size: Some(1),
+            ..Config::default()
+        };
+        assert!(open_found(repo.path(), &small).is_none());
+
+        repo.write(crate::config::OPT_OUT_FILE, "");
+        assert!(open_found(repo.path(), &config).is_none());
+    }
+
+    #[test]
+    fn renders_the_prompt() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.write("a", "b");
+        let path = format!("--no-color --path {:?}", repo.path());
+
+        let ascii = eval(&format!("git_prompt --theme ascii {path}")).unwrap();
+        let ascii = ascii.as_str().unwrap();
+        assert!(ascii.contains("main"), "{ascii}");
+        assert!(ascii.is_ascii(), "{ascii}");
+
+        let format = format!("git_prompt --format '[$branch](bold) $all_status' {path}");
+        assert_eq!(eval(&format).unwrap(), Value::test_string("main ~1"));
+
+        let record = eval(&format!("git_prompt --record {path}")).unwrap();
+        let record = record.as_record().unwrap();
+        assert_eq!(record.get("branch"), Some(&Value::test_string("main")));
+        assert_eq!(record.get("wt_modified"), Some(&Value::test_int(1)));
+    }
+
+    #[test]
+    fn rejects_invalid_flags() {
+        let err = eval("git_prompt --theme neon").unwrap_err();
+        assert!(err.contains("Invalid theme"), "{err}");
+
+        let err = eval("git_prompt --format '[$branch'").unwrap_err();
+        assert!(err.contains("Invalid git_prompt format"), "{err}");
+    }
+
+    #[test]
+    fn renders_nothing_outside_a_repository() {
+        let repo = TempRepo::new();
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        let source = format!("git_prompt --path {:?}", repo.path());
+
+        assert_eq!(eval(&source).unwrap(), Value::test_string(""));
+        let nothing = format!("{source} --nothing");
+        assert_eq!(eval(&nothing).unwrap(), Value::test_nothing());
+    }
+}
@@ -0,0 +1,130 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, Spanned, SyntaxShape, Type, Value};
+
+use crate::theme::THEMES;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptInit;
+
+impl SimplePluginCommand for GitPromptInit {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt init"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::String)])
+            .named(
+                "theme",
+                SyntaxShape::String,
+                format!("symbol theme to set up: {}", THEMES.join(", ")),
+                Some('t'),
+            )
+            .switch(
+                "right",
+                "also set up the right prompt with git_prompt right",
+                Some('r'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Print the config snippet that shows git_prompt in your prompt"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "git_prompt init | save --append $nu.config-path",
+                description: "Add the prompt to your config",
+                result: None,
+            },
+            Example {
+                example: "git_prompt init --theme ascii --right",
+                description: "Show the snippet for plain ASCII symbols on both sides",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        _engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let theme = call.get_flag::<Spanned<String>>("theme")?;
+        if let Some(theme) = &theme {
+            if !THEMES.contains(&theme.item.as_str()) {
+                return Err(LabeledError::new("Invalid theme").with_label(
+                    format!("expected one of: {}", THEMES.join(", ")),
+                    theme.span,
+                ));
+            }
+        }
+
+        let snippet = snippet(
+            theme.as_ref().map(|theme| theme.item.as_str()),
+            call.has_flag("right")?,
+        );
+
+        Ok(Value::string(snippet, call.head))
+    }
+}
+
+fn snippet(theme: Option<&str>, right: bool) -> String {
+    let mut lines = vec!["\n# git_prompt".to_string()];
+
+    // The theme goes in the plugin config rather than a flag, so that both
+    // prompts pick it up.
+    if let Some(theme) = theme.filter(|theme| *theme != "default") {
+        lines.push(format!(
+            "$env.config.plugins.git_prompt = ($env.config.plugins.git_prompt? | default {{}} | merge {{theme: \"{theme}\"}})"
+        ));
+    }
+
+    lines.push(
+        "$env.PROMPT_COMMAND = {|| $\"(pwd | str replace $nu.home-path '~')(git_prompt)\" }"
+            .to_string(),
+    );
+
+    if right {
+        lines.push("$env.PROMPT_COMMAND_RIGHT = {|| git_prompt right }".to_string());
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::eval;
+
+    #[test]
+    fn snippet_sets_the_prompts() {
+        let left = snippet(None, false);
+        assert!(left.contains("$env.PROMPT_COMMAND = "));
+        assert!(!left.contains("PROMPT_COMMAND_RIGHT"));
+        assert!(!left.contains("theme"));
+        assert_eq!(snippet(Some("default"), false), left);
+
+        let both = snippet(Some("ascii"), true);
+        assert!(both.contains("merge {theme: \"ascii\"}"));
+        assert!(both.contains("$env.PROMPT_COMMAND_RIGHT = {|| git_prompt right }"));
+    }
+
+    #[test]
+    fn rejects_unknown_themes() {
+        let snippet = eval("git_prompt init --theme ascii --right").unwrap();
+        assert_eq!(
+            snippet.as_str().unwrap(),
+            super::snippet(Some("ascii"), true)
+        );
+
+        let err = eval("git_prompt init --theme neon").unwrap_err();
+        assert!(err.contains("Invalid theme"), "{err}");
+    }
+}
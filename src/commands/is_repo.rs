@@ -0,0 +1,71 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::target_dir;
+use crate::config::GitEnv;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptIsRepo;
+
+impl SimplePluginCommand for GitPromptIsRepo {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt is-repo"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::Bool)])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "directory to check instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Whether the current directory is inside a git repository"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "if (git_prompt is-repo) { git_prompt } else { '' }",
+            description: "Only render the prompt inside a repository",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let git_env = GitEnv::load(engine)?;
+        let is_repo = target_dir(engine, call)?.is_some_and(|dir| git_env.discover(&dir).is_ok());
+
+        Ok(Value::bool(is_repo, call.head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn checks_the_directory_and_its_parents() {
+        let repo = TempRepo::new();
+        repo.write("src/main.rs", "");
+        let source = format!("git_prompt is-repo --path {:?}", repo.path().join("src"));
+        assert_eq!(eval(&source).unwrap(), Value::test_bool(true));
+
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        assert_eq!(eval(&source).unwrap(), Value::test_bool(false));
+    }
+}
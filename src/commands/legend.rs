@@ -0,0 +1,357 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Signature, Spanned, SyntaxShape, Type, Value,
+};
+
+use crate::config::Config;
+use crate::theme::{self, THEMES};
+use crate::GitPromptPlugin;
+
+pub struct GitPromptLegend;
+
+impl SimplePluginCommand for GitPromptLegend {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt legend"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "theme",
+                SyntaxShape::String,
+                format!("symbol theme to describe: {}", THEMES.join(", ")),
+                Some('t'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Explain every symbol the prompt can show with the current settings"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt legend | where segment == staged",
+            description: "Explain the symbols of the staged counts",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let mut config = Config::load(engine)?;
+
+        if let Some(name) = call.get_flag::<Spanned<String>>("theme")? {
+            theme::apply(&mut config, &name.item)
+                .map_err(|msg| LabeledError::new("Invalid theme").with_label(msg, name.span))?;
+        }
+
+        let span = call.head;
+        let rows = legend(&config)
+            .into_iter()
+            .map(|(segment, symbol, description)| {
+                Value::record(
+                    record! {
+                        "segment" => Value::string(segment, span),
+                        "symbol" => Value::string(symbol, span),
+                        "description" => Value::string(description, span),
+                    },
+                    span,
+                )
+            })
+            .collect();
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+/// Segment, symbol and meaning of every symbol in `config`. Counts follow
+/// their symbol, e.g. `+~2`.
+fn legend(config: &Config) -> Vec<(&'static str, String, &'static str)> {
+    let mut rows = vec![
+        (
+            "remote",
+            config.remote.symbol.clone(),
+            "the branch has an upstream",
+        ),
+        (
+            "remote",
+            config.remote.gone.clone(),
+            "the upstream branch was deleted",
+        ),
+        (
+            "remote",
+            config.remote.local.clone(),
+            "the branch has no upstream",
+        ),
+    ];
+
+    for (_, icon) in &config.remote.icons {
+        rows.push((
+            "remote",
+            icon.clone(),
+            "upstream hosted on a known provider",
+        ));
+    }
+
+    rows.extend([
+        ("staged", config.staged.new.clone(), "new files staged"),
+        (
+            "staged",
+            config.staged.modified.clone(),
+            "modified files staged",
+        ),
+        (
+            "staged",
+            config.staged.renamed.clone(),
+            "renamed files staged",
+        ),
+        (
+            "staged",
+            config.staged.typechange.clone(),
+            "files with a type change staged",
+        ),
+        ("unstaged", config.unstaged.new.clone(), "untracked files"),
+        (
+            "unstaged",
+            config.unstaged.modified.clone(),
+            "modified files not staged",
+        ),
+        (
+            "unstaged",
+            config.unstaged.renamed.clone(),
+            "renamed files not staged",
+        ),
+        (
+            "unstaged",
+            config.unstaged.typechange.clone(),
+            "files with a type change not staged",
+        ),
+        (
+            "deleted",
+            config.deleted.staged.clone(),
+            "deleted files staged",
+        ),
+        (
+            "deleted",
+            config.deleted.unstaged.clone(),
+            "deleted files not staged",
+        ),
+        (
+            "divergence",
+            config.divergence.ahead.clone(),
+            "commits ahead of the upstream",
+        ),
+        (
+            "divergence",
+            config.divergence.behind.clone(),
+            "commits behind the upstream",
+        ),
+        (
+            "divergence",
+            config.divergence.push_ahead.clone(),
+            "commits ahead of the push target",
+        ),
+        (
+            "divergence",
+            config.divergence.push_behind.clone(),
+            "commits behind the push target",
+        ),
+        ("stash", config.stash.symbol.clone(), "stashes"),
+        (
+            "default_branch",
+            config.default_branch.ahead.clone(),
+            "commits ahead of the default branch",
+        ),
+        (
+            "default_branch",
+            config.default_branch.behind.clone(),
+            "commits behind the default branch",
+        ),
+        (
+            "version",
+            config.version.symbol.clone(),
+            "version of the project",
+        ),
+        ("ignored", config.ignored.symbol.clone(), "ignored files"),
+        (
+            "conflicted",
+            config.conflicted.symbol.clone(),
+            "files with merge conflicts",
+        ),
+        (
+            "hidden",
+            config.hidden.skip_worktree_symbol.clone(),
+            "files marked skip-worktree",
+        ),
+        (
+            "hidden",
+            config.hidden.assume_unchanged_symbol.clone(),
+            "files marked assume-unchanged",
+        ),
+        (
+            "lock",
+            config.lock.symbol.clone(),
+            "the index is locked by another git process",
+        ),
+        (
+            "maintenance",
+            config.maintenance.symbol.clone(),
+            "gc or maintenance is running",
+        ),
+        ("state", config.state.merge.clone(), "merge in progress"),
+        ("state", config.state.rebase.clone(), "rebase in progress"),
+        (
+            "state",
+            config.state.rebase_progress.clone(),
+            "rebase in progress, with the step it is at",
+        ),
+        (
+            "state",
+            config.state.cherry_pick.clone(),
+            "cherry-pick in progress",
+        ),
+        ("state", config.state.revert.clone(), "revert in progress"),
+        ("state", config.state.bisect.clone(), "bisect in progress"),
+        (
+            "state",
+            config.state.am.clone(),
+            "patches being applied with git am",
+        ),
+        (
+            "state",
+            config.state.todo_symbol.clone(),
+            "steps left in the operation",
+        ),
+        (
+            "submodules",
+            config.submodules.dirty.clone(),
+            "submodules with changes",
+        ),
+        (
+            "submodules",
+            config.submodules.outdated.clone(),
+            "submodules out of date or uninitialized",
+        ),
+        (
+            "superproject",
+            config.superproject.symbol.clone(),
+            "inside a submodule of this repository",
+        ),
+        ("shallow", config.shallow.symbol.clone(), "shallow clone"),
+        ("promisor", config.promisor.symbol.clone(), "partial clone"),
+        (
+            "identity",
+            config.identity.missing.clone(),
+            "no user.email configured",
+        ),
+        (
+            "identity",
+            config.identity.mismatch.clone(),
+            "user.email isn't the expected one",
+        ),
+        (
+            "worktree",
+            config.worktree.symbol.clone(),
+            "name of the linked worktree",
+        ),
+        (
+            "unpushed",
+            config.unpushed.symbol.clone(),
+            "some branch has unpushed commits",
+        ),
+        (
+            "unpublished",
+            config.unpublished.symbol.clone(),
+            "HEAD isn't on any remote branch",
+        ),
+        (
+            "remotes",
+            config.remotes.symbol.clone(),
+            "number of remotes",
+        ),
+        (
+            "fetch_age",
+            config.fetch_age.symbol.clone(),
+            "time since the last fetch",
+        ),
+        (
+            "lfs_locks",
+            config.lfs_locks.symbol.clone(),
+            "files locked with Git LFS",
+        ),
+        (
+            "diffstat",
+            config.diffstat.insertions.clone(),
+            "inserted lines",
+        ),
+        (
+            "diffstat",
+            config.diffstat.deletions.clone(),
+            "deleted lines",
+        ),
+        (
+            "signature",
+            config.signature.signed.clone(),
+            "HEAD is signed",
+        ),
+        (
+            "signature",
+            config.signature.unsigned.clone(),
+            "HEAD isn't signed",
+        ),
+        (
+            "signature",
+            config.signature.verified.clone(),
+            "the signature of HEAD is valid",
+        ),
+        (
+            "signature",
+            config.signature.invalid.clone(),
+            "the signature of HEAD is invalid",
+        ),
+        (
+            "branch",
+            config.branch.annotated_symbol.clone(),
+            "annotated tag",
+        ),
+        (
+            "branch",
+            config.branch.lightweight_symbol.clone(),
+            "lightweight tag",
+        ),
+    ]);
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{column, eval};
+
+    #[test]
+    fn explains_the_symbols_of_the_theme() {
+        let legend = eval("git_prompt legend").unwrap();
+        assert!(column(&legend, "symbol").contains(&Value::test_string("≡")));
+
+        let legend = eval("git_prompt legend --theme ascii").unwrap();
+        let symbols = column(&legend, "symbol");
+        assert!(symbols.contains(&Value::test_string("$")));
+        assert!(symbols
+            .iter()
+            .all(|symbol| symbol.as_str().unwrap().is_ascii()));
+
+        let err = eval("git_prompt legend --theme fancy").unwrap_err();
+        assert!(err.contains("Invalid theme"), "{err}");
+    }
+}
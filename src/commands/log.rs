@@ -0,0 +1,136 @@
+use git2::Sort;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Signature, Spanned, SyntaxShape, Type, Value,
+};
+
+use crate::commands::discover_repo;
+use crate::time::age_value;
+use crate::GitPromptPlugin;
+
+const DEFAULT_COUNT: usize = 10;
+
+pub struct GitPromptLog;
+
+impl SimplePluginCommand for GitPromptLog {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt log"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "number",
+                SyntaxShape::Int,
+                "number of commits to return, 10 by default",
+                Some('n'),
+            )
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to show the commits of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "List the most recent commits reachable from HEAD"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt log -n 5",
+            description: "Show the last five commits",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let count = match call.get_flag::<Spanned<i64>>("number")? {
+            Some(number) if number.item < 0 => {
+                return Err(LabeledError::new("Invalid number of commits")
+                    .with_label("expected a positive number", number.span));
+            }
+            Some(number) => number.item as usize,
+            None => DEFAULT_COUNT,
+        };
+
+        let repo = discover_repo(engine, call)?;
+        let span = call.head;
+
+        let walk_error = |err: git2::Error| {
+            LabeledError::new("Can't walk the history").with_label(err.message().to_string(), span)
+        };
+
+        let mut revwalk = repo.revwalk().map_err(walk_error)?;
+        revwalk.set_sorting(Sort::TIME).map_err(walk_error)?;
+        // An unborn branch has no history.
+        if revwalk.push_head().is_err() {
+            return Ok(Value::list(vec![], span));
+        }
+
+        let rows = revwalk
+            .flatten()
+            .take(count)
+            .filter_map(|oid| repo.find_commit(oid).ok())
+            .map(|commit| {
+                let sha = commit
+                    .as_object()
+                    .short_id()
+                    .ok()
+                    .and_then(|id| id.as_str().map(str::to_string))
+                    .unwrap_or_else(|| commit.id().to_string());
+
+                Value::record(
+                    record! {
+                        "sha" => Value::string(sha, span),
+                        "author" => Value::string(commit.author().name().unwrap_or_default(), span),
+                        "age" => age_value(commit.time().seconds(), span),
+                        "subject" => Value::string(commit.summary().unwrap_or_default(), span),
+                    },
+                    span,
+                )
+            })
+            .collect();
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{column, eval, TempRepo};
+
+    #[test]
+    fn lists_the_latest_commits() {
+        let repo = TempRepo::new();
+        let path = format!("--path {:?}", repo.path());
+        let unborn = eval(&format!("git_prompt log {path}")).unwrap();
+        assert_eq!(unborn, Value::test_list(vec![]));
+
+        for file in ["a", "b", "c"] {
+            repo.commit_file(file, file);
+        }
+        let log = eval(&format!("git_prompt log {path}")).unwrap();
+        assert_eq!(column(&log, "author")[0], Value::test_string("Test"));
+        assert_eq!(column(&log, "subject").len(), 3);
+
+        let log = eval(&format!("git_prompt log -n 2 {path}")).unwrap();
+        assert_eq!(column(&log, "sha").len(), 2);
+
+        let err = eval(&format!("git_prompt log -n -1 {path}")).unwrap_err();
+        assert!(err.contains("Invalid number of commits"), "{err}");
+    }
+}
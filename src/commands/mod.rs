@@ -0,0 +1,103 @@
+mod branch;
+mod branches;
+mod cache;
+mod compare;
+mod config;
+mod conflicts;
+mod describe;
+mod diffstat;
+mod dirty;
+mod doctor;
+mod fetch;
+mod files;
+mod git_prompt;
+mod init;
+mod is_repo;
+mod legend;
+mod log;
+mod rel_path;
+mod right;
+mod root;
+mod scan;
+mod stashes;
+mod state;
+mod summary;
+mod tag;
+mod timings;
+mod version;
+mod warm;
+mod worktrees;
+
+pub use branch::GitPromptBranch;
+pub use branches::GitPromptBranches;
+pub use cache::GitPromptCacheClear;
+pub use compare::GitPromptCompare;
+pub use config::{GitPromptConfigGet, GitPromptConfigSet};
+pub use conflicts::GitPromptConflicts;
+pub use describe::GitPromptDescribe;
+pub use diffstat::GitPromptDiffstat;
+pub use dirty::GitPromptDirty;
+pub use doctor::GitPromptDoctor;
+pub use fetch::GitPromptFetch;
+pub use files::GitPromptFiles;
+pub use git_prompt::GitPrompt;
+pub use init::GitPromptInit;
+pub use is_repo::GitPromptIsRepo;
+pub use legend::GitPromptLegend;
+pub use log::GitPromptLog;
+pub use rel_path::GitPromptRelPath;
+pub use right::GitPromptRight;
+pub use root::GitPromptRoot;
+pub use scan::GitPromptScan;
+pub use stashes::GitPromptStashes;
+pub use state::GitPromptState;
+pub use summary::GitPromptSummary;
+pub use tag::GitPromptTag;
+pub use timings::GitPromptTimings;
+pub use version::GitPromptVersion;
+pub use warm::GitPromptWarm;
+pub use worktrees::GitPromptWorktrees;
+
+use std::path::PathBuf;
+
+use git2::Repository;
+use nu_plugin::{EngineInterface, EvaluatedCall};
+use nu_protocol::{LabeledError, Spanned};
+
+use crate::config::GitEnv;
+
+/// Directory the command works on: the `--path` flag when given, resolved
+/// against the current directory, otherwise the current directory itself.
+pub fn target_dir(
+    engine: &EngineInterface,
+    call: &EvaluatedCall,
+) -> Result<Option<PathBuf>, LabeledError> {
+    let current_dir = engine.get_current_dir().ok().map(PathBuf::from);
+
+    let Some(path) = call.get_flag::<Spanned<String>>("path")? else {
+        return Ok(current_dir);
+    };
+
+    let path = PathBuf::from(path.item);
+    if path.is_absolute() {
+        return Ok(Some(path));
+    }
+
+    Ok(current_dir.map(|dir| dir.join(path)))
+}
+
+/// Repository containing the directory of `target_dir`, for the commands that
+/// fail outside a repository instead of returning an empty prompt.
+pub fn discover_repo(
+    engine: &EngineInterface,
+    call: &EvaluatedCall,
+) -> Result<Repository, LabeledError> {
+    let dir = target_dir(engine, call)?.ok_or_else(|| {
+        LabeledError::new("No current directory")
+            .with_label("can't find the current directory", call.head)
+    })?;
+
+    GitEnv::load(engine)?.discover(&dir).map_err(|err| {
+        LabeledError::new("Not a git repository").with_label(err.message().to_string(), call.head)
+    })
+}
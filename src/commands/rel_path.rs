@@ -0,0 +1,82 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Value};
+
+use crate::commands::target_dir;
+use crate::config::GitEnv;
+use crate::context::Context;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptRelPath;
+
+impl SimplePluginCommand for GitPromptRelPath {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt rel-path"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "directory to show the relative path of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Current directory relative to the root of the git repository"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let Some(dir) = target_dir(engine, call)? else {
+            return Ok(Value::string("", call.head));
+        };
+
+        let repo = match GitEnv::load(engine)?.discover(&dir) {
+            Ok(repo) => repo,
+            Err(_) => {
+                return Ok(Value::string("", call.head));
+            }
+        };
+
+        let ctx = Context::new(&repo, &dir);
+        let rel_path = ctx
+            .relative_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+
+        Ok(Value::string(rel_path, call.head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn shows_the_path_from_the_root() {
+        let repo = TempRepo::new();
+        repo.write("src/lib/mod.rs", "");
+        let source = format!(
+            "git_prompt rel-path --path {:?}",
+            repo.path().join("src/lib")
+        );
+        assert_eq!(eval(&source).unwrap().as_str().unwrap(), "src/lib");
+
+        let source = format!("git_prompt rel-path --path {:?}", repo.path());
+        assert_eq!(eval(&source).unwrap().as_str().unwrap(), "");
+    }
+}
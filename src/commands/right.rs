@@ -0,0 +1,101 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::git_prompt::{load, render_segments, Render};
+use crate::commands::target_dir;
+use crate::segments;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptRight;
+
+impl SimplePluginCommand for GitPromptRight {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt right"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::String)])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "directory to show the status of instead of the current one",
+                Some('p'),
+            )
+            .switch(
+                "nothing",
+                "return nothing instead of an empty string outside a git repository",
+                None,
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Condensed git status output to show in PROMPT_COMMAND_RIGHT"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![]
+    }
+
+    fn run(
+        &self,
+        plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let (config, target) = load(engine, target_dir(engine, call)?)?;
+        match render_segments(
+            plugin,
+            target.as_ref(),
+            &config,
+            &config.right_segments,
+            None,
+        ) {
+            Render::Segments { segments, .. } => {
+                Ok(Value::string(segments::join(&segments), call.head))
+            }
+            Render::NotRepo if config.nothing_outside_repo || call.has_flag("nothing")? => {
+                Ok(Value::nothing(call.head))
+            }
+            Render::UnsafeOwner => Ok(Value::string(config.unsafe_owner, call.head)),
+            Render::Broken => Ok(Value::string(config.broken, call.head)),
+            Render::NotRepo | Render::Hidden => Ok(Value::string("", call.head)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn renders_the_right_segments() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let source = format!(
+            "$env.config.plugins.git_prompt = {{ color: false, segments: [], right_segments: [branch] }}
+            git_prompt right --path {:?}",
+            repo.path()
+        );
+
+        let right = eval(&source).unwrap();
+        assert!(right.as_str().unwrap().contains("main"), "{right:?}");
+    }
+
+    #[test]
+    fn renders_nothing_outside_a_repository() {
+        let repo = TempRepo::new();
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        let source = format!("git_prompt right --path {:?}", repo.path());
+
+        assert_eq!(eval(&source).unwrap(), Value::test_string(""));
+        let nothing = format!("{source} --nothing");
+        assert_eq!(eval(&nothing).unwrap(), Value::test_nothing());
+    }
+}
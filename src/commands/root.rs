@@ -0,0 +1,90 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::target_dir;
+use crate::config::GitEnv;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptRoot;
+
+impl SimplePluginCommand for GitPromptRoot {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt root"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![
+                (Type::Nothing, Type::String),
+                (Type::Nothing, Type::Nothing),
+            ])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "directory to find the root of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Root of the working tree of the current repository, nothing outside one"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "def --env cdroot [] { cd (git_prompt root) }",
+            description: "Define a command jumping to the root of the repository",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let git_env = GitEnv::load(engine)?;
+        let root = target_dir(engine, call)?
+            .and_then(|dir| git_env.discover(&dir).ok())
+            .and_then(|repo| {
+                repo.workdir()
+                    .map(|workdir| workdir.components().as_path().to_path_buf())
+            });
+
+        Ok(match root {
+            Some(root) => Value::string(root.display().to_string(), call.head),
+            None => Value::nothing(call.head),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn finds_the_root_from_a_subdirectory() {
+        let repo = TempRepo::new();
+        repo.write("src/main.rs", "");
+        let source = format!("git_prompt root --path {:?}", repo.path().join("src"));
+
+        let root = eval(&source).unwrap();
+        assert_eq!(root.as_str().unwrap(), repo.path().to_str().unwrap());
+    }
+
+    #[test]
+    fn is_nothing_outside_a_repository() {
+        let repo = TempRepo::new();
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        let source = format!("git_prompt root --path {:?}", repo.path());
+
+        assert_eq!(eval(&source).unwrap(), Value::test_nothing());
+    }
+}
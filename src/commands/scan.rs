@@ -0,0 +1,146 @@
+use std::path::{Path, PathBuf};
+
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, Spanned, SyntaxShape, Type, Value};
+use walkdir::WalkDir;
+
+use crate::commands::git_prompt::open_found;
+use crate::config::Config;
+use crate::context::Context;
+use crate::record::repo_row;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptScan;
+
+impl SimplePluginCommand for GitPromptScan {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt scan"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .required(
+                "dir",
+                SyntaxShape::Directory,
+                "directory to search for repositories",
+            )
+            .named(
+                "max-depth",
+                SyntaxShape::Int,
+                "how many directories deep to search",
+                Some('d'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Find the git repositories under a directory and show the status of each"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt scan ~/src | where unstaged > 0 or ahead > 0",
+            description: "Find the repositories with uncommitted or unpushed work",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let config = Config::load(engine)?;
+        let dir: Spanned<String> = call.req(0)?;
+        let max_depth = match call.get_flag::<Spanned<i64>>("max-depth")? {
+            Some(depth) => Some(usize::try_from(depth.item).map_err(|_| {
+                LabeledError::new("Invalid depth")
+                    .with_label("expected a positive number", depth.span)
+            })?),
+            None => None,
+        };
+
+        let mut root = PathBuf::from(&dir.item);
+        if root.is_relative() {
+            if let Ok(current_dir) = engine.get_current_dir() {
+                root = PathBuf::from(current_dir).join(root);
+            }
+        }
+
+        if !root.is_dir() {
+            return Err(LabeledError::new("Not a directory")
+                .with_label("can't read this directory", dir.span));
+        }
+
+        let rows = find_repos(&root, max_depth)
+            .iter()
+            .filter_map(|path| {
+                let repo = open_found(path, &config)?;
+                Some(repo_row(&Context::new(&repo, path), call.head))
+            })
+            .collect();
+
+        Ok(Value::list(rows, call.head))
+    }
+}
+
+/// Working trees under `root`, including `root` itself. Repositories aren't
+/// searched further, so submodules and nested clones are left out.
+pub fn find_repos(root: &Path, max_depth: Option<usize>) -> Vec<PathBuf> {
+    let mut walker = WalkDir::new(root).sort_by_file_name();
+    if let Some(max_depth) = max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+
+    let mut repos = Vec::new();
+    let mut entries = walker.into_iter();
+    while let Some(entry) = entries.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        if entry.path().join(".git").exists() {
+            repos.push(entry.path().to_path_buf());
+            entries.skip_current_dir();
+        }
+    }
+
+    repos
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Repository;
+    use nu_protocol::Value;
+
+    use crate::testing::{column, eval, TempRepo};
+
+    #[test]
+    fn shows_a_row_per_repository() {
+        let scratch = TempRepo::new();
+        let root = scratch.path().join("src");
+        Repository::init(root.join("near")).unwrap();
+        Repository::init(root.join("group/far")).unwrap();
+        // Not searched further than a repository.
+        Repository::init(root.join("near/vendored")).unwrap();
+
+        let repos = eval(&format!("git_prompt scan {root:?}")).unwrap();
+        let path = |dir: &str| Value::test_string(root.join(dir).to_str().unwrap());
+        assert_eq!(column(&repos, "path"), [path("group/far"), path("near")]);
+
+        let repos = eval(&format!("git_prompt scan --max-depth 1 {root:?}")).unwrap();
+        assert_eq!(column(&repos, "path"), [path("near")]);
+
+        let err = eval(&format!("git_prompt scan {:?}", root.join("missing"))).unwrap_err();
+        assert!(err.contains("Not a directory"), "{err}");
+    }
+}
@@ -0,0 +1,134 @@
+use git2::Oid;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{record, Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::discover_repo;
+use crate::time::age_value;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptStashes;
+
+impl SimplePluginCommand for GitPromptStashes {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt stashes"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to list the stashes of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "List the stashes of the current repository"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt stashes | where branch == main",
+            description: "Show the stashes made on the main branch",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let mut repo = discover_repo(engine, call)?;
+
+        let mut stashes: Vec<(usize, String, Oid)> = Vec::new();
+        repo.stash_foreach(|index, message, oid| {
+            stashes.push((index, message.to_string(), *oid));
+            true
+        })
+        .map_err(|err| {
+            LabeledError::new("Can't list the stashes")
+                .with_label(err.message().to_string(), call.head)
+        })?;
+
+        let span = call.head;
+        let rows = stashes
+            .into_iter()
+            .map(|(index, message, oid)| {
+                let age = repo
+                    .find_commit(oid)
+                    .map_or(Value::nothing(span), |commit| {
+                        age_value(commit.time().seconds(), span)
+                    });
+                let branch = stash_branch(&message)
+                    .map_or(Value::nothing(span), |branch| Value::string(branch, span));
+
+                Value::record(
+                    record! {
+                        "index" => Value::int(index as i64, span),
+                        "message" => Value::string(message, span),
+                        "branch" => branch,
+                        "age" => age,
+                    },
+                    span,
+                )
+            })
+            .collect();
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+/// Branch a stash was made on, from its message: `WIP on main: …` for
+/// `git stash` and `On main: …` for `git stash push -m`.
+fn stash_branch(message: &str) -> Option<&str> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+
+    rest.split_once(':').map(|(branch, _)| branch)
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::{Repository, StashFlags};
+    use nu_protocol::Value;
+
+    use crate::testing::{column, eval, TempRepo};
+
+    #[test]
+    fn lists_the_stashes_newest_first() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let mut stash = Repository::open(repo.path()).unwrap();
+        let signature = stash.signature().unwrap();
+        for message in ["first", "second"] {
+            repo.write("a", message);
+            stash
+                .stash_save(&signature, message, Some(StashFlags::DEFAULT))
+                .unwrap();
+        }
+
+        let source = format!("git_prompt stashes --path {:?}", repo.path());
+        let stashes = eval(&source).unwrap();
+        assert_eq!(
+            column(&stashes, "index"),
+            [Value::test_int(0), Value::test_int(1)]
+        );
+        assert_eq!(
+            column(&stashes, "message"),
+            [
+                Value::test_string("On main: second"),
+                Value::test_string("On main: first"),
+            ]
+        );
+        assert_eq!(column(&stashes, "branch")[0], Value::test_string("main"));
+    }
+}
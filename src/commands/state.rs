@@ -0,0 +1,90 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::discover_repo;
+use crate::operation::Operation;
+use crate::record::operation_record;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptState;
+
+impl SimplePluginCommand for GitPromptState {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt state"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![
+                (Type::Nothing, Type::record()),
+                (Type::Nothing, Type::Nothing),
+            ])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to show the state of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Operation in progress, such as a merge or a rebase, or nothing"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "(git_prompt state | get kind?) == rebase",
+            description: "Check whether a rebase is in progress",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+
+        Ok(match Operation::init(&repo) {
+            Some(operation) => operation_record(&operation, call.head),
+            None => Value::nothing(call.head),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn describes_the_operation_in_progress() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let source = format!("git_prompt state --path {:?}", repo.path());
+        assert_eq!(eval(&source).unwrap(), Value::test_nothing());
+
+        repo.merge_conflict(&["a"], &[]);
+        let state = eval(&source).unwrap();
+        let state = state.as_record().unwrap();
+        assert_eq!(state.get("kind"), Some(&Value::test_string("merge")));
+        assert_eq!(state.get("source"), Some(&Value::test_string("theirs")));
+    }
+
+    #[test]
+    fn fails_outside_a_repository() {
+        let repo = TempRepo::new();
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        let source = format!("git_prompt state --path {:?}", repo.path());
+
+        let err = eval(&source).unwrap_err();
+        assert!(err.contains("Not a git repository"), "{err}");
+    }
+}
@@ -0,0 +1,184 @@
+use std::path::PathBuf;
+
+use git2::Repository;
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, Type, Value};
+
+use crate::commands::git_prompt::open_found;
+use crate::commands::scan::find_repos;
+use crate::config::Config;
+use crate::context::{file_stamp, Context};
+use crate::record::repo_row;
+use crate::GitPromptPlugin;
+
+/// Files of the git dir whose modification time is part of the cache key of
+/// a row.
+const KEY_FILES: &[&str] = &[
+    "HEAD",
+    "index",
+    "packed-refs",
+    "FETCH_HEAD",
+    "refs/heads",
+    "logs/HEAD",
+];
+
+pub struct GitPromptSummary;
+
+impl SimplePluginCommand for GitPromptSummary {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt summary"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Repositories under the configured workspace roots that are dirty, ahead or behind"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example:
+                "$env.config.plugins.git_prompt.workspace_roots = ['~/src']; git_prompt summary",
+            description: "List the repositories in ~/src that need attention",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let config = Config::load(engine)?;
+        let span = call.head;
+
+        let mut rows = Vec::new();
+        for (path, repo) in workspace_repos(&config) {
+            let key: Vec<String> = KEY_FILES
+                .iter()
+                .map(|file| file_stamp(&repo.path().join(file)))
+                .collect();
+            let key = key.join(":");
+
+            let row = match plugin.rows.get(&path, &key, config.summary_ttl) {
+                Some(row) => row,
+                None => {
+                    let row = repo_row(&Context::new(&repo, &path), span);
+                    plugin.rows.insert(&path, key, row.clone());
+                    row
+                }
+            };
+
+            if needs_attention(&row) {
+                rows.push(row);
+            }
+        }
+
+        Ok(Value::list(rows, span))
+    }
+}
+
+/// Repositories under the workspace roots, as deep as `summary_max_depth`,
+/// that the prompt would open.
+fn workspace_repos(config: &Config) -> Vec<(PathBuf, Repository)> {
+    config
+        .workspace_roots
+        .iter()
+        .flat_map(|root| find_repos(root, Some(config.summary_max_depth)))
+        .filter_map(|path| {
+            let repo = open_found(&path, config)?;
+            Some((path, repo))
+        })
+        .collect()
+}
+
+/// Whether any count of a row from `repo_row` is above zero.
+fn needs_attention(row: &Value) -> bool {
+    let Ok(record) = row.as_record() else {
+        return false;
+    };
+
+    [
+        "staged",
+        "unstaged",
+        "untracked",
+        "conflicted",
+        "ahead",
+        "behind",
+    ]
+    .iter()
+    .any(|column| {
+        record
+            .get(*column)
+            .and_then(|value| value.as_int().ok())
+            .is_some_and(|count| count > 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{column, eval, TempRepo};
+
+    #[test]
+    fn workspace_repos_stop_at_the_max_depth() {
+        let scratch = TempRepo::new();
+        let root = scratch.path().join("work");
+        let near = root.join("near");
+        let far = root.join("a/b/c/far");
+        Repository::init(&near).unwrap();
+        Repository::init(&far).unwrap();
+
+        let config = Config {
+            workspace_roots: vec![root.clone()],
+            ..Config::default()
+        };
+        let found = |config: &Config| -> Vec<PathBuf> {
+            workspace_repos(config)
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect()
+        };
+        assert_eq!(found(&config), vec![near.clone()]);
+
+        let deeper = Config {
+            summary_max_depth: 4,
+            ..config
+        };
+        assert_eq!(found(&deeper), vec![far.clone(), near.clone()]);
+
+        let disabled = Config {
+            disabled_paths: vec![
+                nu_glob::Pattern::new(&format!("{}/a/**", root.display())).unwrap()
+            ],
+            ..deeper
+        };
+        assert_eq!(found(&disabled), vec![near]);
+    }
+
+    #[test]
+    fn lists_the_repositories_that_need_attention() {
+        let scratch = TempRepo::new();
+        let root = scratch.path().join("work");
+        Repository::init(root.join("clean")).unwrap();
+        Repository::init(root.join("dirty")).unwrap();
+        std::fs::write(root.join("dirty/new"), "new").unwrap();
+
+        let source = format!(
+            "$env.config.plugins.git_prompt = {{ workspace_roots: [{root:?}] }}; git_prompt summary"
+        );
+        let summary = eval(&source).unwrap();
+        assert_eq!(
+            column(&summary, "path"),
+            [Value::test_string(root.join("dirty").to_str().unwrap())]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{record, Category, Example, LabeledError, Signature, SyntaxShape, Type, Value};
+
+use crate::commands::discover_repo;
+use crate::context::Context;
+use crate::segments::tag;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptTag;
+
+impl SimplePluginCommand for GitPromptTag {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt tag"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![
+                (Type::Nothing, Type::String),
+                (Type::Nothing, Type::record()),
+                (Type::Nothing, Type::Nothing),
+            ])
+            .switch("exact", "only a tag pointing at HEAD itself", Some('e'))
+            .switch(
+                "record",
+                "return a record with the name, the distance and whether it's exact",
+                Some('r'),
+            )
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to find the tag in instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Most recent tag reachable from HEAD, nothing when there is none"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: "git_prompt tag",
+                description: "Get the nearest tag",
+                result: None,
+            },
+            Example {
+                example: "git_prompt tag --record",
+                description: "Get the nearest tag and how many commits HEAD is past it",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+        let path = repo.workdir().unwrap_or(repo.path()).to_path_buf();
+        let ctx = Context::new(&repo, &path);
+        let span = call.head;
+
+        let exact = call.has_flag("exact")?;
+        let nearest = tag::nearest(&ctx).filter(|(_, distance)| *distance == 0 || !exact);
+        let Some((name, distance)) = nearest else {
+            return Ok(Value::nothing(span));
+        };
+
+        if !call.has_flag("record")? {
+            return Ok(Value::string(name, span));
+        }
+
+        Ok(Value::record(
+            record! {
+                "name" => Value::string(name, span),
+                "distance" => Value::int(distance as i64, span),
+                "exact" => Value::bool(distance == 0, span),
+            },
+            span,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn finds_the_nearest_tag() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.tag("v1.0.0", true);
+        repo.commit_file("b", "b");
+        let path = format!("--path {:?}", repo.path());
+
+        let tag = eval(&format!("git_prompt tag {path}")).unwrap();
+        assert_eq!(tag.as_str().unwrap(), "v1.0.0");
+        let exact = eval(&format!("git_prompt tag --exact {path}")).unwrap();
+        assert_eq!(exact, Value::test_nothing());
+
+        let record = eval(&format!("git_prompt tag --record {path}")).unwrap();
+        let record = record.as_record().unwrap();
+        assert_eq!(record.get("distance"), Some(&Value::test_int(1)));
+        assert_eq!(record.get("exact"), Some(&Value::test_bool(false)));
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    Category, Example, LabeledError, Record, Signature, Span, SyntaxShape, Type, Value,
+};
+
+use crate::cache::SegmentCache;
+use crate::commands::git_prompt::git_dir_size;
+use crate::commands::target_dir;
+use crate::config::Config;
+use crate::context::Context;
+use crate::segments::{tag, Registry};
+use crate::GitPromptPlugin;
+
+pub struct GitPromptTimings;
+
+impl SimplePluginCommand for GitPromptTimings {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt timings"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::record())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to time instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Time each stage of rendering the prompt, without the segment cache"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt timings",
+            description: "Find out which stage makes the prompt slow",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let dir = target_dir(engine, call)?.ok_or_else(|| {
+            LabeledError::new("No current directory")
+                .with_label("can't find the current directory", call.head)
+        })?;
+        let config = Config::load_for(engine, Some(&dir))?;
+
+        let span = call.head;
+        let mut timings = Record::new();
+        let mut time = |stage: &str, start: Instant| {
+            timings.push(stage, duration_value(start.elapsed(), span));
+        };
+        let total = Instant::now();
+
+        let start = Instant::now();
+        let repo = config.git_env.discover(&dir).map_err(|err| {
+            LabeledError::new("Can't open the repository")
+                .with_label(err.message().to_string(), span)
+        })?;
+        time("repo_open", start);
+
+        let start = Instant::now();
+        if let Some(limit) = config.max_repo_size {
+            git_dir_size(repo.commondir(), limit);
+        }
+        time("size_check", start);
+
+        // Each stage fills the context, so the later ones only pay for
+        // themselves.
+        let ctx = Context::new(&repo, &dir);
+
+        let start = Instant::now();
+        ctx.head();
+        time("head", start);
+
+        let start = Instant::now();
+        ctx.status();
+        time("statuses", start);
+
+        let start = Instant::now();
+        ctx.ahead_behind();
+        ctx.push_ahead_behind();
+        time("ahead_behind", start);
+
+        let start = Instant::now();
+        tag::describe(&ctx, false);
+        time("tag", start);
+
+        let start = Instant::now();
+        Registry::new(&config, &config.segments).render(&ctx, &SegmentCache::default());
+        time("render", start);
+
+        time("total", total);
+
+        Ok(Value::record(timings, span))
+    }
+}
+
+fn duration_value(duration: Duration, span: Span) -> Value {
+    Value::duration(duration.as_nanos() as i64, span)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{eval, TempRepo};
+
+    #[test]
+    fn times_every_stage() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let source = format!("git_prompt timings --path {:?}", repo.path());
+
+        let timings = eval(&source).unwrap();
+        let timings = timings.as_record().unwrap();
+        let stages: Vec<&str> = timings.columns().map(String::as_str).collect();
+        assert_eq!(
+            stages,
+            [
+                "repo_open",
+                "size_check",
+                "head",
+                "statuses",
+                "ahead_behind",
+                "tag",
+                "render",
+                "total"
+            ]
+        );
+        assert!(timings.values().all(|time| time.as_duration().is_ok()));
+    }
+
+    #[test]
+    fn fails_outside_a_repository() {
+        let repo = TempRepo::new();
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        let source = format!("git_prompt timings --path {:?}", repo.path());
+
+        let err = eval(&source).unwrap_err();
+        assert!(err.contains("Can't open the repository"), "{err}");
+    }
+}
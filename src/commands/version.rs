@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{record, Category, Example, LabeledError, Signature, Type, Value};
+
+use crate::GitPromptPlugin;
+use crate::{config, overrides};
+
+pub struct GitPromptVersion;
+
+impl SimplePluginCommand for GitPromptVersion {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt version"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::record())])
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Show the plugin version and build information, to include in bug reports"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "git_prompt version",
+            description: "Show the build information",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let span = call.head;
+        let libgit2 = git2::Version::get();
+        let (major, minor, patch) = libgit2.libgit2_version();
+
+        let features = [
+            ("https", libgit2.https()),
+            ("ssh", libgit2.ssh()),
+            ("threads", libgit2.threads()),
+            ("vendored", libgit2.vendored()),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| Value::string(name, span))
+        .collect();
+
+        // Only the files that exist are reported, since only those are read.
+        let existing = |path: Option<PathBuf>| match path {
+            Some(path) if path.is_file() => Value::string(path.to_string_lossy(), span),
+            _ => Value::nothing(span),
+        };
+
+        Ok(Value::record(
+            record! {
+                "version" => Value::string(env!("CARGO_PKG_VERSION"), span),
+                "libgit2" => Value::string(format!("{major}.{minor}.{patch}"), span),
+                "git2" => Value::string(libgit2.crate_version(), span),
+                "features" => Value::list(features, span),
+                "config_path" => existing(config::file_path(engine)?),
+                "overrides_path" => existing(overrides::path()),
+            },
+            span,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::eval;
+
+    #[test]
+    fn reports_the_plugin_and_libgit2_versions() {
+        let version = eval("git_prompt version").unwrap();
+        let version = version.as_record().unwrap();
+
+        assert_eq!(
+            version.get("version"),
+            Some(&Value::test_string(env!("CARGO_PKG_VERSION")))
+        );
+        let libgit2 = version.get("libgit2").unwrap().as_str().unwrap();
+        assert_eq!(libgit2.split('.').count(), 3);
+        assert!(version.get("features").unwrap().as_list().is_ok());
+
+        let missing = "$env.NU_GIT_PROMPT_CONFIG = '/no/such/config.toml'; git_prompt version";
+        let version = eval(missing).unwrap();
+        assert_eq!(
+            version.as_record().unwrap().get("config_path"),
+            Some(&Value::test_nothing())
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{Category, Example, LabeledError, Signature, Spanned, SyntaxShape, Type, Value};
+
+use crate::commands::git_prompt::{load, render_segments};
+use crate::commands::target_dir;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptWarm;
+
+impl SimplePluginCommand for GitPromptWarm {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt warm"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::Nothing)])
+            .optional(
+                "path",
+                SyntaxShape::Directory,
+                "repository to warm up instead of the current directory",
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "Render the prompt into the cache without returning it, so the next prompt is fast"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "$env.config.hooks.env_change.PWD = [{|_, dir| git_prompt warm $dir }]",
+            description: "Warm up the cache whenever the directory changes",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let dir = match call.opt::<Spanned<String>>(0)? {
+            Some(path) => {
+                let path = PathBuf::from(path.item);
+                match engine.get_current_dir() {
+                    Ok(current_dir) if path.is_relative() => {
+                        Some(PathBuf::from(current_dir).join(path))
+                    }
+                    _ => Some(path),
+                }
+            }
+            None => target_dir(engine, call)?,
+        };
+        let (mut config, target) = load(engine, dir)?;
+        // Warming is meant for big repositories, so the size limit doesn't
+        // apply, which also gives the `cached` degrade strategy a prompt.
+        config.max_repo_size = None;
+
+        // The prompts are cached by their segments, so each one is warmed
+        // with the same names `git_prompt` and `git_prompt right` use.
+        let left = match &config.format {
+            Some(format) => format.segments(),
+            None => config.segments.clone(),
+        };
+        for names in [&left, &config.right_segments] {
+            render_segments(plugin, target.as_ref(), &config, names, None);
+        }
+
+        Ok(Value::nothing(call.head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use nu_protocol::Value;
+
+    use crate::testing::{eval, eval_with, TempRepo};
+    use crate::GitPromptPlugin;
+
+    #[test]
+    fn the_next_prompt_reuses_the_warmed_segments() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let plugin = Arc::new(GitPromptPlugin::default());
+        let config = "$env.config.plugins.git_prompt = { color: false, status_ttl: 60, segments: [unstaged] }";
+        let warm = format!("{config}; git_prompt warm {:?}", repo.path());
+        let prompt = format!("{config}; git_prompt --path {:?}", repo.path());
+
+        assert_eq!(eval_with(&plugin, &warm).unwrap(), Value::test_nothing());
+        repo.write("a", "b");
+        let warmed = eval_with(&plugin, &prompt).unwrap();
+        assert_ne!(eval(&prompt).unwrap(), warmed);
+    }
+
+    #[test]
+    fn does_nothing_outside_a_repository() {
+        let repo = TempRepo::new();
+        std::fs::remove_dir_all(repo.path().join(".git")).unwrap();
+        let source = format!("git_prompt warm {:?}", repo.path());
+
+        assert_eq!(eval(&source).unwrap(), Value::test_nothing());
+    }
+}
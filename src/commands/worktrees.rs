@@ -0,0 +1,168 @@
+use git2::{Repository, WorktreeLockStatus};
+use nu_plugin::{EngineInterface, EvaluatedCall, PluginCommand, SimplePluginCommand};
+use nu_protocol::{
+    record, Category, Example, LabeledError, Signature, Span, SyntaxShape, Type, Value,
+};
+
+use crate::commands::discover_repo;
+use crate::repo;
+use crate::GitPromptPlugin;
+
+pub struct GitPromptWorktrees;
+
+impl SimplePluginCommand for GitPromptWorktrees {
+    type Plugin = GitPromptPlugin;
+
+    fn name(&self) -> &str {
+        "git_prompt worktrees"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build(PluginCommand::name(self))
+            .input_output_types(vec![(Type::Nothing, Type::table())])
+            .named(
+                "path",
+                SyntaxShape::Directory,
+                "repository to list the worktrees of instead of the current one",
+                Some('p'),
+            )
+            .category(Category::Experimental)
+    }
+
+    fn description(&self) -> &str {
+        "List the worktrees of the current repository"
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: "cd (git_prompt worktrees | where branch == hotfix | get 0.path)",
+            description: "Jump to the worktree of the hotfix branch",
+            result: None,
+        }]
+    }
+
+    fn run(
+        &self,
+        _plugin: &GitPromptPlugin,
+        engine: &EngineInterface,
+        call: &EvaluatedCall,
+        _input: &Value,
+    ) -> Result<Value, LabeledError> {
+        let repo = discover_repo(engine, call)?;
+
+        // Linked worktrees are only listed from the main repository.
+        let main = repo::open(repo.commondir()).map_err(|err| {
+            LabeledError::new("Can't open the main repository")
+                .with_label(err.message().to_string(), call.head)
+        })?;
+
+        let mut rows = Vec::new();
+        if let Some(workdir) = main.workdir() {
+            rows.push(worktree_row(
+                &main,
+                workdir.components().as_path().display().to_string(),
+                false,
+                false,
+                call.head,
+            ));
+        }
+
+        let names = main.worktrees().map_err(|err| {
+            LabeledError::new("Can't list the worktrees")
+                .with_label(err.message().to_string(), call.head)
+        })?;
+
+        for name in names.iter().flatten() {
+            let Ok(worktree) = main.find_worktree(name) else {
+                continue;
+            };
+
+            let locked = !matches!(worktree.is_locked(), Ok(WorktreeLockStatus::Unlocked));
+            let prunable = worktree.is_prunable(None).unwrap_or(false);
+            let path = worktree.path().display().to_string();
+
+            match repo::checked(|| Repository::open_from_worktree(&worktree)) {
+                Ok(repo) => rows.push(worktree_row(&repo, path, locked, prunable, call.head)),
+                Err(_) => rows.push(Value::record(
+                    record! {
+                        "path" => Value::string(path, call.head),
+                        "branch" => Value::nothing(call.head),
+                        "head" => Value::nothing(call.head),
+                        "locked" => Value::bool(locked, call.head),
+                        "prunable" => Value::bool(prunable, call.head),
+                    },
+                    call.head,
+                )),
+            }
+        }
+
+        Ok(Value::list(rows, call.head))
+    }
+}
+
+fn worktree_row(
+    repo: &Repository,
+    path: String,
+    locked: bool,
+    prunable: bool,
+    span: Span,
+) -> Value {
+    let head = repo.head().ok();
+    let branch = head
+        .as_ref()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand())
+        .map_or(Value::nothing(span), |branch| Value::string(branch, span));
+    let sha = head
+        .as_ref()
+        .and_then(|head| head.target())
+        .map_or(Value::nothing(span), |oid| {
+            Value::string(oid.to_string()[..7].to_string(), span)
+        });
+
+    Value::record(
+        record! {
+            "path" => Value::string(path, span),
+            "branch" => branch,
+            "head" => sha,
+            "locked" => Value::bool(locked, span),
+            "prunable" => Value::bool(prunable, span),
+        },
+        span,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use nu_protocol::Value;
+
+    use crate::testing::{column, eval, TempRepo};
+
+    #[test]
+    fn lists_the_main_and_linked_worktrees() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let linked = repo.path().join("linked");
+        repo.repo.worktree("feature", &linked, None).unwrap();
+
+        // Listed the same from either worktree.
+        for dir in [repo.path(), linked.as_path()] {
+            let worktrees = eval(&format!("git_prompt worktrees --path {dir:?}")).unwrap();
+            assert_eq!(
+                column(&worktrees, "path"),
+                [
+                    Value::test_string(repo.path().to_str().unwrap()),
+                    Value::test_string(linked.to_str().unwrap()),
+                ]
+            );
+            assert_eq!(
+                column(&worktrees, "branch"),
+                [Value::test_string("main"), Value::test_string("feature")]
+            );
+            assert_eq!(
+                column(&worktrees, "locked"),
+                [Value::test_bool(false), Value::test_bool(false)]
+            );
+        }
+    }
+}
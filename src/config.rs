@@ -0,0 +1,1274 @@
+use std::path::{Path, PathBuf};
+
+use git2::{ConfigLevel, Repository, RepositoryOpenFlags};
+use nu_glob::Pattern;
+
+use nu_plugin::EngineInterface;
+use nu_protocol::engine::Closure;
+use nu_protocol::{LabeledError, Record, Span, Spanned, Value};
+
+use crate::format::{check_style, Format};
+use crate::overrides;
+use crate::repo;
+use crate::segments;
+use crate::segments::{
+    AgeConfig, BranchConfig, ConflictedConfig, DefaultBranchConfig, DeletedConfig,
+    DescriptionConfig, DiffstatConfig, DivergenceConfig, FetchAgeConfig, HiddenConfig,
+    IdentityConfig, IgnoredConfig, LfsLocksConfig, LockConfig, MaintenanceConfig, PromisorConfig,
+    RelPathConfig, RemoteConfig, RemotesConfig, ShallowConfig, SignatureConfig, StagedConfig,
+    StashConfig, StateConfig, SubjectConfig, SubmodulesConfig, SuperprojectConfig,
+    UnpublishedConfig, UnpushedConfig, UnstagedConfig, VersionConfig, WorktreeConfig,
+};
+use crate::theme;
+use crate::toml;
+
+/// Segments shown when the configuration doesn't list them explicitly.
+pub const DEFAULT_SEGMENTS: &[&str] = &[
+    "superproject",
+    "remote",
+    "branch",
+    "worktree",
+    "state",
+    "lock",
+    "maintenance",
+    "staged",
+    "unstaged",
+    "divergence",
+    "stash",
+    "ignored",
+    "deleted",
+    "conflicted",
+    "submodules",
+    "shallow",
+    "promisor",
+    "identity",
+];
+
+/// Segments of `git_prompt right` when the configuration doesn't list them.
+pub const DEFAULT_RIGHT_SEGMENTS: &[&str] = &["remote", "divergence", "age"];
+
+/// Size of the git dir above which the prompt is hidden, unless
+/// `max_repo_size` says otherwise.
+pub const DEFAULT_MAX_REPO_SIZE: u64 = 10_000_000;
+
+/// Segments shown by `NU_GIT_PROMPT_COMPACT`.
+pub const COMPACT_SEGMENTS: &[&str] = &["branch", "state", "divergence"];
+
+/// Prefix of the environment variables overriding single settings, e.g.
+/// `NU_GIT_PROMPT_THEME` or `NU_GIT_PROMPT_DIVERGENCE__BEHIND_WARNING`.
+pub const ENV_PREFIX: &str = "NU_GIT_PROMPT_";
+
+/// Environment variable with the path of the TOML config file, used instead
+/// of `config.toml` in the config directory.
+pub const CONFIG_FILE_VAR: &str = "NU_GIT_PROMPT_CONFIG";
+
+/// What the prompt shows for repositories over `max_repo_size`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Degrade {
+    /// Nothing.
+    #[default]
+    Blank,
+    /// The branch only.
+    Branch,
+    /// The branch and the operation in progress.
+    BranchState,
+    /// The last prompt rendered for the repository, e.g. by `git_prompt
+    /// warm`, followed by the `stale` marker.
+    Cached,
+}
+
+/// Settings for the whole plugin, one field per segment.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Names of the segments to render, in order.
+    pub segments: Vec<String>,
+    /// Names of the segments rendered by `git_prompt right`, in order.
+    pub right_segments: Vec<String>,
+    /// Return `nothing` instead of an empty string outside a git repository.
+    pub nothing_outside_repo: bool,
+    /// Closure receiving the status record and returning the prompt string,
+    /// used instead of the built-in rendering.
+    pub renderer: Option<Spanned<Closure>>,
+    /// Starship-style format string used instead of `segments`.
+    pub format: Option<Format>,
+    /// Apply the styles of the format string.
+    pub color: bool,
+    /// Repositories under these paths are opened even when owned by another
+    /// user, like git's `safe.directory`.
+    pub trusted_paths: Vec<PathBuf>,
+    /// Directories matching these globs get no prompt, without looking at
+    /// the filesystem at all, e.g. `/mnt/nfs/**`.
+    pub disabled_paths: Vec<Pattern>,
+    /// Directories matching these globs always get a prompt. When set, the
+    /// directories matching none of them get no prompt.
+    pub enabled_paths: Vec<Pattern>,
+    /// Shown instead of the prompt for repositories owned by another user.
+    pub unsafe_owner: String,
+    /// Shown instead of the prompt when the repository can't be read.
+    pub broken: String,
+    /// Directories searched for repositories by `git_prompt summary`.
+    pub workspace_roots: Vec<PathBuf>,
+    /// How many directories below each workspace root `git_prompt summary`
+    /// searches for repositories.
+    pub summary_max_depth: usize,
+    /// Seconds a row of `git_prompt summary` is reused for.
+    pub summary_ttl: i64,
+    /// Seconds the status scan of a repository, and the `submodules` and
+    /// `diffstat` segments, are reused for while its HEAD, index and state
+    /// files don't change. Edits in the working tree only show up once it
+    /// expires, so the default `0` rescans on every prompt.
+    pub status_ttl: i64,
+    /// Once the status is older than `status_ttl`, show it with `stale`
+    /// anyway and scan again in the background, instead of waiting.
+    pub background_status: bool,
+    /// Show nothing at all, e.g. for a single session.
+    pub disabled: bool,
+    /// Size in bytes of the git dir above which the prompt is hidden, `None`
+    /// for no limit.
+    pub max_repo_size: Option<u64>,
+    /// What is shown instead for repositories over `max_repo_size`.
+    pub degrade: Degrade,
+    /// Shown after a cached prompt by the `cached` degrade strategy, and
+    /// after a stale status with `background_status`.
+    pub stale: String,
+    /// Repository picked by `$env.GIT_DIR`, not a setting.
+    pub git_env: GitEnv,
+
+    pub remote: RemoteConfig,
+    pub branch: BranchConfig,
+    pub state: StateConfig,
+    pub staged: StagedConfig,
+    pub unstaged: UnstagedConfig,
+    pub divergence: DivergenceConfig,
+    pub stash: StashConfig,
+    pub default_branch: DefaultBranchConfig,
+    pub ignored: IgnoredConfig,
+    pub deleted: DeletedConfig,
+    pub conflicted: ConflictedConfig,
+    pub rel_path: RelPathConfig,
+    pub version: VersionConfig,
+    pub age: AgeConfig,
+    pub subject: SubjectConfig,
+    pub signature: SignatureConfig,
+    pub identity: IdentityConfig,
+    pub lock: LockConfig,
+    pub diffstat: DiffstatConfig,
+    pub submodules: SubmodulesConfig,
+    pub superproject: SuperprojectConfig,
+    pub shallow: ShallowConfig,
+    pub promisor: PromisorConfig,
+    pub lfs_locks: LfsLocksConfig,
+    pub hidden: HiddenConfig,
+    pub unpushed: UnpushedConfig,
+    pub unpublished: UnpublishedConfig,
+    pub remotes: RemotesConfig,
+    pub description: DescriptionConfig,
+    pub worktree: WorktreeConfig,
+    pub fetch_age: FetchAgeConfig,
+    pub maintenance: MaintenanceConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            segments: DEFAULT_SEGMENTS.iter().map(|s| s.to_string()).collect(),
+            right_segments: DEFAULT_RIGHT_SEGMENTS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            nothing_outside_repo: false,
+            renderer: None,
+            format: None,
+            color: true,
+            trusted_paths: Vec::new(),
+            disabled_paths: Vec::new(),
+            enabled_paths: Vec::new(),
+            unsafe_owner: "⚠ unsafe owner".to_string(),
+            broken: "git!".to_string(),
+            workspace_roots: Vec::new(),
+            summary_max_depth: 3,
+            summary_ttl: 60,
+            status_ttl: 0,
+            background_status: false,
+            disabled: false,
+            max_repo_size: Some(DEFAULT_MAX_REPO_SIZE),
+            degrade: Degrade::default(),
+            stale: "⧗".to_string(),
+            git_env: GitEnv::default(),
+            remote: RemoteConfig::default(),
+            branch: BranchConfig::default(),
+            state: StateConfig::default(),
+            staged: StagedConfig::default(),
+            unstaged: UnstagedConfig::default(),
+            divergence: DivergenceConfig::default(),
+            stash: StashConfig::default(),
+            default_branch: DefaultBranchConfig::default(),
+            ignored: IgnoredConfig::default(),
+            deleted: DeletedConfig::default(),
+            conflicted: ConflictedConfig::default(),
+            rel_path: RelPathConfig::default(),
+            version: VersionConfig::default(),
+            age: AgeConfig::default(),
+            subject: SubjectConfig::default(),
+            signature: SignatureConfig::default(),
+            identity: IdentityConfig::default(),
+            lock: LockConfig::default(),
+            diffstat: DiffstatConfig::default(),
+            submodules: SubmodulesConfig::default(),
+            superproject: SuperprojectConfig::default(),
+            shallow: ShallowConfig::default(),
+            promisor: PromisorConfig::default(),
+            lfs_locks: LfsLocksConfig::default(),
+            hidden: HiddenConfig::default(),
+            unpushed: UnpushedConfig::default(),
+            unpublished: UnpublishedConfig::default(),
+            remotes: RemotesConfig::default(),
+            description: DescriptionConfig::default(),
+            worktree: WorktreeConfig::default(),
+            fetch_age: FetchAgeConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Keys of the settings that aren't sections.
+    pub const KEYS: &'static [&'static str] = &[
+        "renderer",
+        "format",
+        "segments",
+        "right_segments",
+        "nothing_outside_repo",
+        "color",
+        "unsafe_owner",
+        "broken",
+        "summary_max_depth",
+        "summary_ttl",
+        "status_ttl",
+        "background_status",
+        "disabled",
+        "max_repo_size",
+        "degrade",
+        "stale",
+        "trusted_paths",
+        "disabled_paths",
+        "enabled_paths",
+        "workspace_roots",
+        "theme",
+    ];
+
+    /// Keys of the records with the settings of each segment.
+    pub const SECTIONS: &'static [&'static str] = &[
+        "remote",
+        "branch",
+        "state",
+        "staged",
+        "unstaged",
+        "divergence",
+        "stash",
+        "default_branch",
+        "ignored",
+        "deleted",
+        "conflicted",
+        "rel_path",
+        "version",
+        "age",
+        "subject",
+        "signature",
+        "identity",
+        "lock",
+        "diffstat",
+        "submodules",
+        "superproject",
+        "shallow",
+        "promisor",
+        "lfs_locks",
+        "hidden",
+        "unpushed",
+        "unpublished",
+        "remotes",
+        "description",
+        "worktree",
+        "fetch_age",
+        "maintenance",
+    ];
+
+    /// Reads the settings from the TOML config file, with
+    /// `$env.config.plugins.git_prompt`, the ones saved by
+    /// `git_prompt config set` and the `NU_GIT_PROMPT_*` environment
+    /// variables on top, in that order.
+    pub fn load(engine: &EngineInterface) -> Result<Self, LabeledError> {
+        let mut config = Self::from_record(&effective(engine)?)?;
+        config.git_env = GitEnv::load(engine)?;
+        Ok(config)
+    }
+
+    /// Like `load`, with the `prompt.*` git config of the repository at `dir`
+    /// applied before the environment variables. Repositories that opted out
+    /// get a disabled prompt.
+    pub fn load_for(engine: &EngineInterface, dir: Option<&Path>) -> Result<Self, LabeledError> {
+        let mut config = Self::load(engine)?;
+
+        if dir.is_some_and(|dir| !config.allows_path(dir)) {
+            config.disabled = true;
+            return Ok(config);
+        }
+
+        if let Some(repo) = dir.and_then(|dir| config.git_env.discover(dir).ok()) {
+            config.apply_repo(engine, &repo)?;
+        }
+
+        Ok(config)
+    }
+
+    /// Applies the `prompt.*` git config of `repo` before the environment
+    /// variables, or disables the prompt when `repo` opted out.
+    pub fn apply_repo(
+        &mut self,
+        engine: &EngineInterface,
+        repo: &Repository,
+    ) -> Result<(), LabeledError> {
+        if opt_out(repo).is_some() {
+            self.disabled = true;
+            return Ok(());
+        }
+
+        // The environment variables were applied by `load` already, but they
+        // have to win over the repository too.
+        let repo_settings = repo_overrides(repo);
+        if !repo_settings.is_empty() {
+            self.update(&repo_settings)?;
+            self.update(&env_overrides(engine)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether `dir` gets a prompt according to `enabled_paths` and
+    /// `disabled_paths`. Only the path itself is matched, the filesystem
+    /// isn't touched.
+    pub fn allows_path(&self, dir: &Path) -> bool {
+        if self
+            .enabled_paths
+            .iter()
+            .any(|pattern| pattern.matches_path(dir))
+        {
+            return true;
+        }
+
+        self.enabled_paths.is_empty()
+            && !self
+                .disabled_paths
+                .iter()
+                .any(|pattern| pattern.matches_path(dir))
+    }
+
+    /// Builds the settings from a record shaped like the plugin config.
+    pub fn from_record(record: &Record) -> Result<Self, LabeledError> {
+        let mut config = Self::default();
+        config.update(record)?;
+
+        Ok(config)
+    }
+
+    /// Applies the settings present in `record`, leaving the rest as is.
+    pub fn update(&mut self, record: &Record) -> Result<(), LabeledError> {
+        check_keys(record, &[Self::KEYS, Self::SECTIONS].concat())?;
+
+        if let Some(renderer) = lookup(record, "renderer") {
+            let span = renderer.span();
+            self.renderer = match renderer {
+                Value::Nothing { .. } => None,
+                value => Some(Spanned {
+                    item: value
+                        .as_closure()
+                        .map_err(|_| invalid("renderer", "a closure", value))?
+                        .clone(),
+                    span,
+                }),
+            };
+        }
+
+        if let Some(format) = lookup(record, "format") {
+            self.format = match format {
+                Value::Nothing { .. } => None,
+                value => {
+                    let format = value
+                        .as_str()
+                        .map_err(|_| invalid("format", "a string", value))?;
+                    Some(Format::parse(format).map_err(|msg| {
+                        LabeledError::new("Invalid git_prompt format").with_label(msg, value.span())
+                    })?)
+                }
+            };
+        }
+
+        for key in ["segments", "right_segments"] {
+            if let Some(value) = lookup(record, key) {
+                check_segments(key, value)?;
+            }
+        }
+
+        set_strings(record, "segments", &mut self.segments)?;
+        set_strings(record, "right_segments", &mut self.right_segments)?;
+        set_bool(
+            record,
+            "nothing_outside_repo",
+            &mut self.nothing_outside_repo,
+        )?;
+        set_bool(record, "color", &mut self.color)?;
+        set_string(record, "unsafe_owner", &mut self.unsafe_owner)?;
+        set_string(record, "broken", &mut self.broken)?;
+        set_usize(record, "summary_max_depth", &mut self.summary_max_depth)?;
+        set_seconds(record, "summary_ttl", &mut self.summary_ttl)?;
+        set_seconds(record, "status_ttl", &mut self.status_ttl)?;
+        set_bool(record, "background_status", &mut self.background_status)?;
+        set_bool(record, "disabled", &mut self.disabled)?;
+
+        if let Some(size) = lookup(record, "max_repo_size") {
+            self.max_repo_size = repo_size(size)?;
+        }
+
+        if let Some(degrade) = lookup(record, "degrade") {
+            self.degrade = match degrade.as_str() {
+                Ok("blank") => Degrade::Blank,
+                Ok("branch") => Degrade::Branch,
+                Ok("branch_state") => Degrade::BranchState,
+                Ok("cached") => Degrade::Cached,
+                _ => {
+                    return Err(LabeledError::new("Invalid git_prompt setting `degrade`")
+                        .with_label(
+                            "expected one of: blank, branch, branch_state, cached",
+                            degrade.span(),
+                        ))
+                }
+            };
+        }
+        set_string(record, "stale", &mut self.stale)?;
+
+        if let Some(paths) = lookup(record, "trusted_paths") {
+            self.trusted_paths = strings("trusted_paths", paths)?
+                .into_iter()
+                .map(PathBuf::from)
+                .collect();
+        }
+
+        if let Some(globs) = lookup(record, "disabled_paths") {
+            self.disabled_paths = path_patterns("disabled_paths", globs)?;
+        }
+
+        if let Some(globs) = lookup(record, "enabled_paths") {
+            self.enabled_paths = path_patterns("enabled_paths", globs)?;
+        }
+
+        if let Some(roots) = lookup(record, "workspace_roots") {
+            self.workspace_roots = strings("workspace_roots", roots)?
+                .iter()
+                .map(|root| expand_home(root))
+                .collect();
+        }
+
+        // The theme goes first, so that symbols set explicitly win over it.
+        if let Some(theme) = lookup(record, "theme") {
+            let name = theme
+                .as_str()
+                .map_err(|_| invalid("theme", "a string", theme))?;
+            theme::apply(self, name)
+                .map_err(|msg| LabeledError::new("Invalid theme").with_label(msg, theme.span()))?;
+        }
+
+        macro_rules! update_segments {
+            ($($segment:ident),* $(,)?) => {
+                $(
+                    if let Some(value) = lookup(record, stringify!($segment)) {
+                        self.$segment.update(value).map_err(|err| {
+                            err.with_help(format!("in the `{}` settings", stringify!($segment)))
+                        })?;
+                    }
+                )*
+            };
+        }
+
+        update_segments!(
+            remote,
+            branch,
+            state,
+            staged,
+            unstaged,
+            divergence,
+            stash,
+            default_branch,
+            ignored,
+            deleted,
+            conflicted,
+            rel_path,
+            version,
+            age,
+            subject,
+            signature,
+            identity,
+            lock,
+            diffstat,
+            submodules,
+            superproject,
+            shallow,
+            promisor,
+            lfs_locks,
+            hidden,
+            unpushed,
+            unpublished,
+            remotes,
+            description,
+            worktree,
+            fetch_age,
+            maintenance,
+        );
+
+        Ok(())
+    }
+}
+
+/// The config file merged with the plugin config record, the saved
+/// overrides and the environment variables.
+pub fn effective(engine: &EngineInterface) -> Result<Record, LabeledError> {
+    let plugin_config = match engine.get_plugin_config()? {
+        Some(value) => value.into_record()?,
+        None => Record::new(),
+    };
+
+    Ok(layered([
+        load_file(engine)?,
+        plugin_config,
+        overrides::load(Span::unknown())?,
+        env_overrides(engine)?,
+    ]))
+}
+
+/// Merges `layers` in order, each one over the ones before it.
+fn layered(layers: impl IntoIterator<Item = Record>) -> Record {
+    let mut record = Record::new();
+    for layer in layers {
+        overrides::merge(&mut record, &layer);
+    }
+
+    record
+}
+
+/// `$env.GIT_DIR` and `$env.GIT_WORK_TREE`, which point git at a repository
+/// regardless of the current directory, e.g. for a bare dotfiles repository,
+/// and `$env.GIT_CEILING_DIRECTORIES`, where the search for a repository stops
+/// going up, e.g. above network mounts.
+///
+/// They are read from the engine, since the plugin process doesn't see the
+/// variables exported after nushell started it.
+#[derive(Debug, Clone, Default)]
+pub struct GitEnv {
+    pub git_dir: Option<PathBuf>,
+    pub work_tree: Option<PathBuf>,
+    pub ceiling_dirs: Vec<PathBuf>,
+}
+
+impl GitEnv {
+    /// Reads the variables, relative paths being resolved against the
+    /// current directory like git does.
+    pub fn load(engine: &EngineInterface) -> Result<Self, LabeledError> {
+        let current_dir = engine.get_current_dir().ok().map(PathBuf::from);
+        let var = |name: &str| -> Result<Option<PathBuf>, LabeledError> {
+            let Some(value) = engine.get_env_var(name)? else {
+                return Ok(None);
+            };
+            let path = expand_home(value.as_str()?);
+            if path.as_os_str().is_empty() {
+                return Ok(None);
+            }
+
+            Ok(Some(match &current_dir {
+                Some(dir) if path.is_relative() => dir.join(path),
+                _ => path,
+            }))
+        };
+
+        // Like git, only absolute paths count. Nushell turns the variable
+        // into a list when it's listed in `ENV_CONVERSIONS`.
+        let ceiling_dirs = match engine.get_env_var("GIT_CEILING_DIRECTORIES")? {
+            Some(Value::List { vals, .. }) => vals
+                .iter()
+                .filter_map(|dir| dir.as_str().ok().map(PathBuf::from))
+                .collect(),
+            Some(Value::String { val, .. }) => std::env::split_paths(&val).collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            git_dir: var("GIT_DIR")?,
+            work_tree: var("GIT_WORK_TREE")?,
+            ceiling_dirs: ceiling_dirs
+                .into_iter()
+                .filter(|dir: &PathBuf| dir.is_absolute())
+                .collect(),
+        })
+    }
+
+    /// Repository of `GIT_DIR` when it's set, else the one containing `dir`,
+    /// searched up to the ceiling directories.
+    pub fn discover(&self, dir: &Path) -> Result<Repository, git2::Error> {
+        repo::checked(|| self.find(dir))
+    }
+
+    /// Like `discover`, without libgit2's check that the repository belongs
+    /// to the current user.
+    pub fn discover_trusted(&self, dir: &Path) -> Result<Repository, git2::Error> {
+        repo::unchecked(|| self.find(dir))
+    }
+
+    fn find(&self, dir: &Path) -> Result<Repository, git2::Error> {
+        let repo = match &self.git_dir {
+            Some(git_dir) => Repository::open(git_dir)?,
+            None => Repository::open_ext(dir, RepositoryOpenFlags::empty(), &self.ceiling_dirs)?,
+        };
+
+        if let Some(work_tree) = &self.work_tree {
+            repo.set_workdir(work_tree, false)?;
+        }
+
+        Ok(repo)
+    }
+}
+
+/// Marker file at the root of a working tree that turns the prompt off for it.
+pub const OPT_OUT_FILE: &str = ".nogitprompt";
+
+/// How `repo` turned the prompt off, with `prompt.enabled=false` in its git
+/// config or an `OPT_OUT_FILE`, `None` when it didn't.
+pub fn opt_out(repo: &Repository) -> Option<&'static str> {
+    let disabled = repo
+        .config()
+        .and_then(|config| config.get_bool("prompt.enabled"))
+        .is_ok_and(|enabled| !enabled);
+    if disabled {
+        return Some("prompt.enabled is false in the git config");
+    }
+
+    repo.workdir()
+        .is_some_and(|workdir| workdir.join(OPT_OUT_FILE).exists())
+        .then_some("the working tree has a .nogitprompt file")
+}
+
+/// Settings in the `prompt` section of the local git config of `repo`, e.g.
+/// `prompt.theme` or `prompt.divergence.behindWarning`. Keys given more than
+/// once become lists.
+fn repo_overrides(repo: &Repository) -> Record {
+    match repo.config() {
+        Ok(mut git_config) => prompt_settings(&mut git_config),
+        Err(_) => Record::new(),
+    }
+}
+
+/// The `prompt.*` entries of the local and worktree levels of `git_config`.
+fn prompt_settings(git_config: &mut git2::Config) -> Record {
+    let mut record = Record::new();
+    let Ok(snapshot) = git_config.snapshot() else {
+        return record;
+    };
+    let Ok(mut entries) = snapshot.entries(Some(r"^prompt\.")) else {
+        return record;
+    };
+
+    let mut settings: Vec<(String, Vec<String>)> = Vec::new();
+    while let Some(Ok(entry)) = entries.next() {
+        if !matches!(entry.level(), ConfigLevel::Local | ConfigLevel::Worktree) {
+            continue;
+        }
+
+        let (Some(name), Some(value)) = (entry.name(), entry.value()) else {
+            continue;
+        };
+        let key = name.trim_start_matches("prompt.").to_string();
+        if key == "enabled" {
+            continue;
+        }
+
+        match settings.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, values)) => values.push(value.to_string()),
+            None => settings.push((key, vec![value.to_string()])),
+        }
+    }
+
+    let span = Span::unknown();
+    for (key, mut values) in settings {
+        let value = match values.len() {
+            1 => setting_value(Value::string(values.remove(0), span)),
+            _ => Value::list(
+                values
+                    .into_iter()
+                    .map(|value| Value::string(value, span))
+                    .collect(),
+                span,
+            ),
+        };
+        overrides::set(&mut record, &key, value);
+    }
+
+    record
+}
+
+/// Settings given by `NU_GIT_PROMPT_*` environment variables. The rest of
+/// the name is the setting, with `__` between nested keys, except for
+/// `COMPACT`, which swaps the segments for `COMPACT_SEGMENTS`, and `DISABLE`.
+fn env_overrides(engine: &EngineInterface) -> Result<Record, LabeledError> {
+    env_settings(engine.get_env_vars()?)
+}
+
+/// Settings of the `NU_GIT_PROMPT_*` variables among `vars`.
+fn env_settings(vars: impl IntoIterator<Item = (String, Value)>) -> Result<Record, LabeledError> {
+    let mut vars: Vec<(String, Value)> = vars
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let key = name.strip_prefix(ENV_PREFIX)?.to_string();
+            (name != CONFIG_FILE_VAR).then_some((key, setting_value(value)))
+        })
+        .collect();
+
+    // Compact goes first, so that explicit segments still win over it.
+    vars.sort_by_key(|(key, _)| (key != "COMPACT", key.clone()));
+
+    let mut record = Record::new();
+    for (key, value) in vars {
+        match key.as_str() {
+            "COMPACT" => {
+                if value.as_bool()? {
+                    let segments = COMPACT_SEGMENTS
+                        .iter()
+                        .map(|segment| Value::string(*segment, value.span()))
+                        .collect();
+                    record.insert("segments", Value::list(segments, value.span()));
+                }
+            }
+            "DISABLE" => {
+                record.insert("disabled", value);
+            }
+            key => overrides::set(&mut record, &key.to_lowercase().replace("__", "."), value),
+        }
+    }
+
+    Ok(record)
+}
+
+/// Settings from environment variables and git config are strings, so the
+/// ones that look like a bool or a number are taken as such.
+fn setting_value(value: Value) -> Value {
+    let span = value.span();
+    let Value::String { val, .. } = &value else {
+        return value;
+    };
+
+    match val.as_str() {
+        "true" => Value::bool(true, span),
+        "false" => Value::bool(false, span),
+        text => match text.parse::<i64>() {
+            Ok(int) => Value::int(int, span),
+            Err(_) => value,
+        },
+    }
+}
+
+/// Directory of the plugin in the user's config directory,
+/// `$XDG_CONFIG_HOME/nu_plugin_git_prompt`.
+pub fn config_dir() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_dir.join("nu_plugin_git_prompt"))
+}
+
+/// Path of the TOML config file, from `$env.NU_GIT_PROMPT_CONFIG` or
+/// `config.toml` in the config directory.
+pub fn file_path(engine: &EngineInterface) -> Result<Option<PathBuf>, LabeledError> {
+    if let Some(path) = engine.get_env_var(CONFIG_FILE_VAR)? {
+        return Ok(Some(expand_home(path.as_str()?)));
+    }
+
+    Ok(config_dir().map(|dir| dir.join("config.toml")))
+}
+
+/// Reads the TOML config file, an empty record when there is none.
+fn load_file(engine: &EngineInterface) -> Result<Record, LabeledError> {
+    match file_path(engine)? {
+        Some(path) => read_file(&path),
+        None => Ok(Record::new()),
+    }
+}
+
+/// Reads the TOML config file at `path`, an empty record when it can't be
+/// read.
+fn read_file(path: &Path) -> Result<Record, LabeledError> {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return Ok(Record::new());
+    };
+
+    toml::parse(&text, Span::unknown()).map_err(|msg| {
+        LabeledError::new("Invalid git_prompt config file")
+            .with_label(format!("{}: {msg}", path.display()), Span::unknown())
+    })
+}
+
+/// Globs of a list of paths, which can start with `~`.
+fn path_patterns(key: &str, value: &Value) -> Result<Vec<Pattern>, LabeledError> {
+    strings(key, value)?
+        .iter()
+        .map(|glob| {
+            Pattern::new(&expand_home(glob).to_string_lossy()).map_err(|err| {
+                LabeledError::new("Invalid path glob")
+                    .with_label(format!("{glob}: {}", err.msg), value.span())
+            })
+        })
+        .collect()
+}
+
+/// Limit of `max_repo_size`: a filesize, a number of bytes, a string such as
+/// `3GB`, or `unlimited` and `nothing` for no limit.
+fn repo_size(value: &Value) -> Result<Option<u64>, LabeledError> {
+    let expected = || invalid("max_repo_size", "a filesize or \"unlimited\"", value);
+
+    let bytes = match value {
+        Value::Nothing { .. } => return Ok(None),
+        Value::Filesize { val, .. } => val.get(),
+        Value::Int { val, .. } => *val,
+        Value::String { val, .. } if val == "unlimited" => return Ok(None),
+        Value::String { val, .. } => parse_size(val).ok_or_else(expected)?,
+        _ => return Err(expected()),
+    };
+
+    u64::try_from(bytes).map(Some).map_err(|_| expected())
+}
+
+/// Bytes of a size such as `100MB` or `1.5 GiB`.
+fn parse_size(text: &str) -> Option<i64> {
+    let text = text.trim().to_lowercase();
+    let unit_start = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(unit_start);
+
+    let multiplier: f64 = match unit.trim() {
+        "" | "b" => 1.0,
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+
+    let number: f64 = number.parse().ok()?;
+    Some((number * multiplier) as i64)
+}
+
+/// Fails on the first name in the list at `key` that isn't a segment.
+fn check_segments(key: &str, value: &Value) -> Result<(), LabeledError> {
+    let names = segments::names();
+    for name in strings(key, value)? {
+        if !names.contains(&name.as_str()) {
+            return Err(
+                LabeledError::new(format!("Unknown segment `{name}`")).with_label(
+                    format!("expected one of: {}", names.join(", ")),
+                    value.span(),
+                ),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces a leading `~` with the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(home).join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Value at `key` of `record`. Keys also match ignoring case, `_` and `-`,
+/// since git lowercases config names and doesn't allow `_` in them.
+pub fn lookup<'a>(record: &'a Record, key: &str) -> Option<&'a Value> {
+    record.get(key).or_else(|| {
+        let key = normalize(key);
+        record
+            .iter()
+            .find(|(column, _)| normalize(column) == key)
+            .map(|(_, value)| value)
+    })
+}
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Fails on the first key of `record` that isn't one of `keys`.
+pub fn check_keys(record: &Record, keys: &[&str]) -> Result<(), LabeledError> {
+    for (key, value) in record.iter() {
+        let known = keys.iter().any(|known| normalize(known) == normalize(key));
+        if !known {
+            return Err(
+                LabeledError::new(format!("Unknown git_prompt setting `{key}`")).with_label(
+                    format!("expected one of: {}", keys.join(", ")),
+                    value.span(),
+                ),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Error for a setting whose value isn't of the `expected` kind.
+pub fn invalid(key: &str, expected: &str, value: &Value) -> LabeledError {
+    LabeledError::new(format!("Invalid git_prompt setting `{key}`")).with_label(
+        format!("expected {expected}, found {}", value.get_type()),
+        value.span(),
+    )
+}
+
+/// The record of a section of the settings, e.g. `divergence`.
+pub fn section<'a>(key: &str, value: &'a Value) -> Result<&'a Record, LabeledError> {
+    value
+        .as_record()
+        .map_err(|_| invalid(key, "a record", value))
+}
+
+/// Sets `field` to the string at `key` of `record`, when it's there.
+pub fn set_string(record: &Record, key: &str, field: &mut String) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        *field = as_string(key, value)?;
+    }
+
+    Ok(())
+}
+
+/// Like `set_string`, with `nothing` clearing the field.
+pub fn set_optional_string(
+    record: &Record,
+    key: &str,
+    field: &mut Option<String>,
+) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        *field = match value {
+            Value::Nothing { .. } => None,
+            value => Some(as_string(key, value)?),
+        };
+    }
+
+    Ok(())
+}
+
+/// Like `set_string`, for a starship-style style such as `bold red`.
+pub fn set_style(record: &Record, key: &str, field: &mut String) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        let style = as_string(key, value)?;
+        check_style(&style).map_err(|msg| {
+            LabeledError::new(format!("Invalid git_prompt setting `{key}`"))
+                .with_label(msg, value.span())
+        })?;
+        *field = style;
+    }
+
+    Ok(())
+}
+
+pub fn set_bool(record: &Record, key: &str, field: &mut bool) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        *field = value.as_bool().map_err(|_| invalid(key, "a bool", value))?;
+    }
+
+    Ok(())
+}
+
+pub fn set_usize(record: &Record, key: &str, field: &mut usize) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        *field = value
+            .as_int()
+            .ok()
+            .and_then(|int| usize::try_from(int).ok())
+            .ok_or_else(|| invalid(key, "a positive int", value))?;
+    }
+
+    Ok(())
+}
+
+/// Sets `field` to a number of seconds, given as an int or a duration.
+pub fn set_seconds(record: &Record, key: &str, field: &mut i64) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        *field = match value {
+            Value::Duration { val, .. } => val / 1_000_000_000,
+            Value::Int { val, .. } => *val,
+            value => return Err(invalid(key, "an int or a duration", value)),
+        };
+    }
+
+    Ok(())
+}
+
+/// Sets `field` to the columns and values of the record at `key`, in order.
+pub fn set_pairs(
+    record: &Record,
+    key: &str,
+    field: &mut Vec<(String, String)>,
+) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        let pairs = value
+            .as_record()
+            .map_err(|_| invalid(key, "a record of strings", value))?;
+
+        field.clear();
+        for (column, value) in pairs {
+            field.push((column.clone(), as_string(key, value)?));
+        }
+    }
+
+    Ok(())
+}
+
+/// Sets `field` to the list at `key`, which can also be a single string of
+/// items separated by commas or spaces, as in environment variables.
+pub fn set_strings(
+    record: &Record,
+    key: &str,
+    field: &mut Vec<String>,
+) -> Result<(), LabeledError> {
+    if let Some(value) = lookup(record, key) {
+        *field = strings(key, value)?;
+    }
+
+    Ok(())
+}
+
+/// Items of a list of strings, or of a string separated by commas or spaces.
+fn strings(key: &str, value: &Value) -> Result<Vec<String>, LabeledError> {
+    if let Value::String { val, .. } = value {
+        return Ok(val
+            .split([',', ' '])
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect());
+    }
+
+    value
+        .as_list()
+        .map_err(|_| invalid(key, "a list of strings", value))?
+        .iter()
+        .map(|item| as_string(key, item))
+        .collect()
+}
+
+fn as_string(key: &str, value: &Value) -> Result<String, LabeledError> {
+    value
+        .as_str()
+        .map(str::to_string)
+        .map_err(|_| invalid(key, "a string", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    /// Record of the TOML in `text`, like a source of settings.
+    fn layer(text: &str) -> Record {
+        record(text).into_record().unwrap()
+    }
+
+    #[test]
+    fn plugin_config_is_read_into_the_settings() {
+        let plugin_config =
+            layer("segments = [\"branch\"]\nstatus_ttl = 5\n\n[divergence]\nahead = \"A\"");
+        let config = Config::from_record(&layered([Record::new(), plugin_config])).unwrap();
+
+        assert_eq!(config.segments, vec!["branch"]);
+        assert_eq!(config.status_ttl, 5);
+        assert_eq!(config.divergence.ahead, "A");
+        assert_eq!(config.divergence.behind, DivergenceConfig::default().behind);
+    }
+
+    #[test]
+    fn file_is_under_every_other_source() {
+        let repo = TempRepo::new();
+        repo.write(
+            "config.toml",
+            "status_ttl = 5\n\n[divergence]\nahead = \"A\"\nbehind = \"B\"",
+        );
+        let file = read_file(&repo.path().join("config.toml")).unwrap();
+        let plugin_config = layer("[divergence]\nbehind = \"b\"");
+        let config = Config::from_record(&layered([file, plugin_config])).unwrap();
+
+        assert_eq!(config.status_ttl, 5);
+        assert_eq!(config.divergence.ahead, "A");
+        assert_eq!(config.divergence.behind, "b");
+    }
+
+    #[test]
+    fn saved_overrides_win_over_the_plugin_config() {
+        let file = layer("[stash]\nsymbol = \"f\"");
+        let plugin_config = layer("color = false\n\n[stash]\nsymbol = \"p\"");
+        let saved = layer("[stash]\nsymbol = \"s\"");
+        let config = Config::from_record(&layered([file, plugin_config, saved])).unwrap();
+
+        assert_eq!(config.stash.symbol, "s");
+        assert!(!config.color);
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Record {
+        env_settings(
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), Value::test_string(*value))),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn env_vars_name_nested_settings() {
+        let settings = env(&[
+            ("NU_GIT_PROMPT_STATUS_TTL", "5"),
+            ("NU_GIT_PROMPT_DIVERGENCE__BEHIND_WARNING", "10"),
+            ("NU_GIT_PROMPT_STASH__SYMBOL", "S"),
+            ("NU_GIT_PROMPT_CONFIG", "/etc/prompt.toml"),
+            ("HOME", "/home/user"),
+        ]);
+        let config = Config::from_record(&settings).unwrap();
+
+        assert_eq!(config.status_ttl, 5);
+        assert_eq!(config.divergence.behind_warning, Some(10));
+        assert_eq!(config.stash.symbol, "S");
+        assert_eq!(settings.len(), 3);
+    }
+
+    #[test]
+    fn env_compact_gives_way_to_explicit_segments() {
+        let compact = Config::from_record(&env(&[("NU_GIT_PROMPT_COMPACT", "true")])).unwrap();
+        assert_eq!(compact.segments, COMPACT_SEGMENTS);
+
+        let explicit = env(&[
+            ("NU_GIT_PROMPT_SEGMENTS", "branch"),
+            ("NU_GIT_PROMPT_COMPACT", "true"),
+        ]);
+        assert_eq!(
+            lookup(&explicit, "segments"),
+            Some(&Value::test_string("branch"))
+        );
+
+        let disabled = Config::from_record(&env(&[("NU_GIT_PROMPT_DISABLE", "true")])).unwrap();
+        assert!(disabled.disabled);
+    }
+
+    #[test]
+    fn env_vars_win_over_every_other_source() {
+        let file = layer("color = false\nstatus_ttl = 1");
+        let plugin_config = layer("status_ttl = 2");
+        let saved = layer("status_ttl = 3");
+        let settings = layered([
+            file,
+            plugin_config,
+            saved,
+            env(&[("NU_GIT_PROMPT_STATUS_TTL", "4")]),
+        ]);
+        let config = Config::from_record(&settings).unwrap();
+
+        assert_eq!(config.status_ttl, 4);
+        assert!(!config.color);
+    }
+
+    #[test]
+    fn repo_settings_come_from_the_local_git_config() {
+        let repo = TempRepo::new();
+        let global = repo.path().join("global.gitconfig");
+        let local = repo.path().join("local.gitconfig");
+        std::fs::write(&global, "[prompt]\n\ttheme = ascii\n\tcolor = false\n").unwrap();
+        std::fs::write(
+            &local,
+            "[prompt]\n\tcolor = true\n\tenabled = true\n\tsegments = branch\n\tsegments = stash\n\
+             [prompt \"divergence\"]\n\tbehindWarning = 10\n",
+        )
+        .unwrap();
+
+        let mut git_config = git2::Config::new().unwrap();
+        git_config
+            .add_file(&global, ConfigLevel::Global, false)
+            .unwrap();
+        git_config
+            .add_file(&local, ConfigLevel::Local, false)
+            .unwrap();
+        let settings = prompt_settings(&mut git_config);
+
+        assert_eq!(lookup(&settings, "theme"), None);
+        assert_eq!(lookup(&settings, "enabled"), None);
+        let mut config = Config {
+            color: false,
+            ..Config::default()
+        };
+        config.update(&settings).unwrap();
+        assert!(config.color);
+        assert_eq!(config.segments, vec!["branch", "stash"]);
+        assert_eq!(config.divergence.behind_warning, Some(10));
+    }
+
+    #[test]
+    fn lookup_ignores_case_and_separators() {
+        let settings = layer("behindWarning = 1\nmax-tags = 2\nstatus_ttl = 3");
+        assert_eq!(
+            lookup(&settings, "behind_warning"),
+            Some(&Value::test_int(1))
+        );
+        assert_eq!(lookup(&settings, "max_tags"), Some(&Value::test_int(2)));
+        assert_eq!(lookup(&settings, "STATUS-TTL"), Some(&Value::test_int(3)));
+        assert_eq!(lookup(&settings, "status"), None);
+        assert_eq!(normalize("Behind_Warning"), normalize("behindwarning"));
+    }
+
+    #[test]
+    fn missing_files_are_empty_and_invalid_ones_fail() {
+        let repo = TempRepo::new();
+        assert!(read_file(&repo.path().join("missing.toml"))
+            .unwrap()
+            .is_empty());
+
+        repo.write("config.toml", "status_ttl = ");
+        let err = read_file(&repo.path().join("config.toml")).unwrap_err();
+        assert!(
+            format!("{err:?}").contains("config.toml: line 1"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn parse_size_reads_the_units() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("100MB"), Some(100_000_000));
+        assert_eq!(parse_size("1.5 GiB"), Some(1_610_612_736));
+        assert_eq!(parse_size(" 2kb "), Some(2_000));
+
+        assert_eq!(parse_size("3 parsecs"), None);
+        assert_eq!(parse_size("GB"), None);
+        assert_eq!(parse_size(""), None);
+    }
+
+    #[test]
+    fn repo_size_accepts_unlimited() {
+        let span = Span::test_data();
+        assert_eq!(repo_size(&Value::string("unlimited", span)).unwrap(), None);
+        assert_eq!(repo_size(&Value::nothing(span)).unwrap(), None);
+        assert_eq!(repo_size(&Value::int(2048, span)).unwrap(), Some(2048));
+        assert_eq!(repo_size(&Value::string("1KiB", span)).unwrap(), Some(1024));
+
+        assert!(repo_size(&Value::int(-1, span)).is_err());
+        assert!(repo_size(&Value::bool(true, span)).is_err());
+    }
+}
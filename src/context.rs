@@ -0,0 +1,364 @@
+use std::cell::OnceCell;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use git2::{BranchType, Oid, Repository};
+
+use crate::operation::Operation;
+use crate::status::GitStatus;
+
+/// Files of the git dir whose changes make a cached status stale.
+const STATUS_FILES: &[&str] = &[
+    "HEAD",
+    "index",
+    "ORIG_HEAD",
+    "MERGE_HEAD",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "config",
+];
+
+/// Repository data shared by every segment during a single render.
+///
+/// Anything that more than one segment needs is computed lazily here, so the
+/// repository is only queried once no matter how many segments ask for it.
+pub struct Context<'a> {
+    pub repo: &'a Repository,
+    pub path: &'a Path,
+    head: OnceCell<HeadInfo>,
+    ahead_behind: OnceCell<(u16, u16)>,
+    push_ahead_behind: OnceCell<(u16, u16)>,
+    operation: OnceCell<Option<Operation>>,
+    status: OnceCell<Option<GitStatus>>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(repo: &'a Repository, path: &'a Path) -> Self {
+        Self {
+            repo,
+            path,
+            head: OnceCell::new(),
+            ahead_behind: OnceCell::new(),
+            push_ahead_behind: OnceCell::new(),
+            operation: OnceCell::new(),
+            status: OnceCell::new(),
+        }
+    }
+
+    pub fn head(&self) -> &HeadInfo {
+        self.head.get_or_init(|| HeadInfo::init(self.repo))
+    }
+
+    /// Commits the current branch is ahead of and behind its upstream.
+    pub fn ahead_behind(&self) -> (u16, u16) {
+        *self
+            .ahead_behind
+            .get_or_init(|| self.divergence(self.head().upstream))
+    }
+
+    /// Commits the current branch is ahead of and behind its push target.
+    pub fn push_ahead_behind(&self) -> (u16, u16) {
+        *self
+            .push_ahead_behind
+            .get_or_init(|| self.divergence(self.head().push))
+    }
+
+    fn divergence(&self, other: Option<Oid>) -> (u16, u16) {
+        if let (Some(local), Some(other)) = (self.head().local, other) {
+            if let Ok((ahead, behind)) = self.repo.graph_ahead_behind(local, other) {
+                return (ahead as u16, behind as u16);
+            }
+        }
+
+        (0, 0)
+    }
+
+    /// Operation in progress, such as a merge or a rebase.
+    pub fn operation(&self) -> Option<&Operation> {
+        self.operation
+            .get_or_init(|| Operation::init(self.repo))
+            .as_ref()
+    }
+
+    /// Current directory relative to the root of the working tree, empty at
+    /// the root itself and `None` for bare repositories.
+    pub fn relative_path(&self) -> Option<PathBuf> {
+        let workdir = self.repo.workdir()?.canonicalize().ok()?;
+        let path = self.path.canonicalize().ok()?;
+        path.strip_prefix(workdir).ok().map(Path::to_path_buf)
+    }
+
+    /// Modification time of a file or directory inside the git dir, to be
+    /// used as part of a segment cache key.
+    pub fn git_file_stamp(&self, name: &str) -> String {
+        file_stamp(&self.repo.path().join(name))
+    }
+
+    pub fn status(&self) -> Option<&GitStatus> {
+        self.status
+            .get_or_init(|| GitStatus::init(self.repo))
+            .as_ref()
+    }
+
+    /// Uses `status` instead of scanning, e.g. one kept from an earlier
+    /// render. Does nothing once the status is known.
+    pub fn set_status(&self, status: GitStatus) {
+        let _ = self.status.set(Some(status));
+    }
+
+    /// The status, only if a segment asked for it and the scan succeeded.
+    pub fn scanned_status(&self) -> Option<&GitStatus> {
+        self.status.get().and_then(Option::as_ref)
+    }
+
+    /// Everything the status depends on besides the working tree: HEAD, the
+    /// index, the files of an operation in progress and the git config.
+    pub fn status_key(&self) -> String {
+        let head = self.repo.head().ok().and_then(|head| head.target());
+        let stamps: Vec<String> = STATUS_FILES
+            .iter()
+            .map(|name| self.git_file_stamp(name))
+            .collect();
+
+        format!("{head:?}:{}", stamps.join(":"))
+    }
+
+    /// Whether `index.lock` exists in the git dir.
+    pub fn index_locked(&self) -> bool {
+        self.repo.path().join("index.lock").exists()
+    }
+
+    /// Whether the status scan was run and failed.
+    pub fn status_failed(&self) -> bool {
+        matches!(self.status.get(), Some(None))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HeadInfo {
+    pub branch: String,
+    pub remote: String,
+    /// Name of the remote the upstream belongs to, e.g. `origin`.
+    pub remote_name: String,
+    /// The branch has an upstream configured whose ref no longer exists.
+    pub upstream_gone: bool,
+    /// Commit the current branch points at.
+    pub local: Option<Oid>,
+    /// Commit the upstream of the current branch points at.
+    pub upstream: Option<Oid>,
+    /// Commit the push target of the current branch points at.
+    pub push: Option<Oid>,
+}
+
+impl HeadInfo {
+    pub fn init(repo: &Repository) -> Self {
+        let mut local: Option<Oid> = None;
+        let mut upstream_oid: Option<Oid> = None;
+        let mut push: Option<Oid> = None;
+
+        let mut remote = String::new();
+        let mut remote_name = String::new();
+        let mut upstream_gone = false;
+
+        let branch = branch_name(repo);
+
+        if let Ok(local_branch) = repo.find_branch(&branch, BranchType::Local) {
+            local = local_branch.get().target();
+
+            remote = if let Ok(upstream) = local_branch.upstream() {
+                upstream_oid = upstream.get().target();
+
+                if let Some(refname) = local_branch.get().name() {
+                    if let Ok(buf) = repo.branch_upstream_remote(refname) {
+                        remote_name = buf.as_str().unwrap_or_default().to_string();
+                    }
+                }
+
+                if let Ok(Some(name)) = upstream.name() {
+                    name.to_string()
+                } else {
+                    String::new()
+                }
+            } else {
+                // An upstream is configured but its remote-tracking ref is
+                // missing, e.g. deleted on the remote.
+                upstream_gone = local_branch
+                    .get()
+                    .name()
+                    .is_some_and(|refname| repo.branch_upstream_name(refname).is_ok());
+
+                String::new()
+            };
+
+            push = push_target(repo, &branch, &remote_name);
+        }
+
+        Self {
+            branch,
+            remote,
+            remote_name,
+            upstream_gone,
+            local,
+            upstream: upstream_oid,
+            push,
+        }
+    }
+}
+
+/// Name of the current branch, or the short id of HEAD when it's detached,
+/// without looking up anything else.
+pub fn branch_name(repo: &Repository) -> String {
+    match repo.head() {
+        Ok(reference) => match reference.shorthand() {
+            Some("HEAD") => {
+                if let Ok(commit) = reference.peel_to_commit() {
+                    let mut id = String::with_capacity(8);
+                    for byte in &commit.id().as_bytes()[..4] {
+                        write!(&mut id, "{byte:02x}").unwrap();
+                    }
+                    id
+                } else {
+                    "HEAD".to_string()
+                }
+            }
+            Some(name) => name.to_string(),
+            None => "HEAD".to_string(),
+        },
+        Err(ref err) if err.code() == git2::ErrorCode::BareRepo => "master".to_string(),
+        Err(_) if repo.is_empty().unwrap_or(false) => "master".to_string(),
+        Err(_) => "HEAD".to_string(),
+    }
+}
+
+/// Remote-tracking ref `branch` is pushed to: `branch.<name>.pushRemote`,
+/// then `remote.pushDefault`, then the remote of the upstream.
+fn push_target(repo: &Repository, branch: &str, upstream_remote: &str) -> Option<Oid> {
+    let config = repo.config().ok()?;
+    let remote = config
+        .get_string(&format!("branch.{branch}.pushRemote"))
+        .or_else(|_| config.get_string("remote.pushDefault"))
+        .ok()
+        .or_else(|| (!upstream_remote.is_empty()).then(|| upstream_remote.to_string()))?;
+
+    repo.refname_to_id(&format!("refs/remotes/{remote}/{branch}"))
+        .ok()
+}
+
+/// Modification time of `path` as a string, `-` when it doesn't exist.
+pub fn file_stamp(path: &Path) -> String {
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+
+    match modified {
+        Some(duration) => duration.as_nanos().to_string(),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn reads_the_branch_and_its_upstream() {
+        let repo = TempRepo::new();
+        let base = repo.commit_file("a", "a");
+        repo.set_upstream("https://example.com/repo.git", base);
+        let head = repo.commit_file("b", "b");
+
+        let ctx = Context::new(&repo.repo, repo.path());
+        let info = ctx.head();
+        assert_eq!(info.branch, "main");
+        assert_eq!(info.remote, "origin/main");
+        assert_eq!(info.remote_name, "origin");
+        assert_eq!(info.local, Some(head));
+        assert_eq!(info.upstream, Some(base));
+        assert_eq!(ctx.ahead_behind(), (1, 0));
+    }
+
+    #[test]
+    fn names_a_detached_head_by_its_commit() {
+        let repo = TempRepo::new();
+        let head = repo.commit_file("a", "a");
+        repo.repo.set_head_detached(head).unwrap();
+
+        let name = branch_name(&repo.repo);
+        assert_eq!(name, head.to_string()[..8]);
+    }
+
+    #[test]
+    fn marks_an_upstream_whose_ref_is_gone() {
+        let repo = TempRepo::new();
+        let head = repo.commit_file("a", "a");
+        repo.set_upstream("https://example.com/repo.git", head);
+        repo.repo
+            .find_reference("refs/remotes/origin/main")
+            .unwrap()
+            .delete()
+            .unwrap();
+
+        let info = HeadInfo::init(&repo.repo);
+        assert!(info.upstream_gone);
+        assert_eq!(info.upstream, None);
+        assert_eq!(info.remote, "");
+    }
+
+    #[test]
+    fn push_target_follows_push_remote() {
+        let repo = TempRepo::new();
+        let base = repo.commit_file("a", "a");
+        repo.set_upstream("https://example.com/repo.git", base);
+        let head = repo.commit_file("b", "b");
+        assert_eq!(HeadInfo::init(&repo.repo).push, Some(base));
+
+        repo.repo
+            .remote("fork", "https://example.com/fork.git")
+            .unwrap();
+        repo.repo
+            .reference("refs/remotes/fork/main", head, true, "test")
+            .unwrap();
+        let mut config = repo.repo.config().unwrap();
+        config.set_str("remote.pushDefault", "fork").unwrap();
+        assert_eq!(HeadInfo::init(&repo.repo).push, Some(head));
+
+        config.set_str("branch.main.pushRemote", "origin").unwrap();
+        let ctx = Context::new(&repo.repo, repo.path());
+        assert_eq!(ctx.head().push, Some(base));
+        assert_eq!(ctx.push_ahead_behind(), (1, 0));
+    }
+
+    #[test]
+    fn status_key_follows_head_and_the_index() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let key = || Context::new(&repo.repo, repo.path()).status_key();
+        let first = key();
+        assert_eq!(key(), first);
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        repo.write("b", "b");
+        assert_eq!(key(), first);
+        repo.stage("b");
+        let staged = key();
+        assert_ne!(staged, first);
+
+        repo.commit("Add b");
+        assert_ne!(key(), staged);
+    }
+
+    #[test]
+    fn relative_path_starts_at_the_working_tree() {
+        let repo = TempRepo::new();
+        repo.write("src/lib/mod.rs", "");
+        let dir = repo.path().join("src/lib");
+
+        let ctx = Context::new(&repo.repo, &dir);
+        assert_eq!(ctx.relative_path(), Some(PathBuf::from("src/lib")));
+        let ctx = Context::new(&repo.repo, repo.path());
+        assert_eq!(ctx.relative_path(), Some(PathBuf::new()));
+    }
+}
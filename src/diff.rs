@@ -0,0 +1,38 @@
+use git2::{Diff, Repository};
+
+/// Changes staged in the index, compared with HEAD.
+pub fn staged(repo: &Repository) -> Result<Diff<'_>, git2::Error> {
+    let head = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(_) => None,
+    };
+
+    repo.diff_tree_to_index(head.as_ref(), None, None)
+}
+
+/// Changes in the working tree that are not staged yet.
+pub fn unstaged(repo: &Repository) -> Result<Diff<'_>, git2::Error> {
+    repo.diff_index_to_workdir(None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn splits_staged_and_unstaged_changes() {
+        let repo = TempRepo::new();
+        repo.write("a", "a");
+        repo.stage("a");
+        // Before the first commit everything in the index is staged.
+        assert_eq!(staged(&repo.repo).unwrap().deltas().len(), 1);
+
+        repo.commit("First");
+        repo.write("a", "b");
+        repo.write("b", "b");
+        repo.stage("b");
+        assert_eq!(staged(&repo.repo).unwrap().deltas().len(), 1);
+        assert_eq!(unstaged(&repo.repo).unwrap().deltas().len(), 1);
+    }
+}
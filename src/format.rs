@@ -0,0 +1,413 @@
+//! Starship-style format strings.
+//!
+//! A format is plain text with `$variable`s and `[text](style)` groups, e.g.
+//! `[$symbol $branch](bold purple) $all_status$ahead_behind`. A variable is
+//! either a segment name or one of the starship aliases below. A group whose
+//! variables all rendered empty is dropped entirely, like starship does.
+//!
+//! | starship variable           | segments                                          |
+//! |-----------------------------|---------------------------------------------------|
+//! | `$symbol`                   | `remote`                                          |
+//! | `$git_branch`, `$branch`    | `branch`                                          |
+//! | `$remote_branch`            | `remote`                                          |
+//! | `$ahead_behind`             | `divergence`                                      |
+//! | `$all_status`, `$git_status`| `staged unstaged ignored deleted conflicted`      |
+//! | `$staged`                   | `staged`                                          |
+//! | `$modified`                 | `modified`, the `~` count of `unstaged`           |
+//! | `$untracked`                | `untracked`, the `?` count of `unstaged`          |
+//! | `$renamed`                  | `renamed`, the `->` count of `unstaged`           |
+//! | `$deleted`                  | `deleted`                                         |
+//! | `$conflicted`               | `conflicted`                                      |
+//! | `$stashed`                  | `stash`                                           |
+//! | `$git_state`, `$state`      | `state`                                           |
+
+use nu_ansi_term::{Color, Style};
+
+use crate::segments::Segment;
+
+/// Parsed format string.
+#[derive(Debug, Clone)]
+pub struct Format {
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Text(String),
+    Variable(String),
+    Group { items: Vec<Item>, style: String },
+}
+
+impl Format {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        let mut chars = format.chars().peekable();
+        let items = parse_items(&mut chars, false)?;
+        Ok(Self { items })
+    }
+
+    /// Segment names referenced by the format, with aliases expanded.
+    pub fn segments(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        collect_segments(&self.items, &mut names);
+        names
+    }
+
+    /// Renders the format, leaving out the group styles unless `color` is set.
+    pub fn render(&self, segments: &[Segment], color: bool) -> String {
+        render_items(&self.items, segments, color).0
+    }
+}
+
+/// Segment names a variable stands for.
+fn expand(variable: &str) -> Vec<&str> {
+    match variable {
+        "symbol" | "remote_branch" => vec!["remote"],
+        "git_branch" => vec!["branch"],
+        "ahead_behind" => vec!["divergence"],
+        "stashed" => vec!["stash"],
+        "git_state" => vec!["state"],
+        "all_status" | "git_status" => {
+            vec!["staged", "unstaged", "ignored", "deleted", "conflicted"]
+        }
+        name => vec![name],
+    }
+}
+
+fn collect_segments(items: &[Item], names: &mut Vec<String>) {
+    for item in items {
+        match item {
+            Item::Text(_) => {}
+            Item::Variable(variable) => {
+                for name in expand(variable) {
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+            Item::Group { items, .. } => collect_segments(items, names),
+        }
+    }
+}
+
+/// Returns the rendered text, and whether any variable in it was non-empty
+/// (or `true` when it has no variables at all).
+fn render_items(items: &[Item], segments: &[Segment], color: bool) -> (String, bool) {
+    let mut output = String::new();
+    let mut has_variables = false;
+    let mut any_value = false;
+
+    for item in items {
+        match item {
+            Item::Text(text) => output.push_str(text),
+            Item::Variable(variable) => {
+                has_variables = true;
+                let texts: Vec<&str> = expand(variable)
+                    .into_iter()
+                    .filter_map(|name| segments.iter().find(|s| s.name == name))
+                    .map(|s| s.text.as_str())
+                    .collect();
+                if !texts.is_empty() {
+                    any_value = true;
+                    output.push_str(&texts.join(" "));
+                }
+            }
+            Item::Group { items, style } => {
+                let (text, shown) = render_items(items, segments, color);
+                if shown {
+                    has_variables = true;
+                    any_value = true;
+                    if color {
+                        output.push_str(&parse_style(style).paint(text).to_string());
+                    } else {
+                        output.push_str(&text);
+                    }
+                }
+            }
+        }
+    }
+
+    (output, any_value || !has_variables)
+}
+
+fn parse_items(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    in_group: bool,
+) -> Result<Vec<Item>, String> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    text.push(escaped);
+                }
+            }
+            '$' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+
+                if name.is_empty() {
+                    text.push('$');
+                    continue;
+                }
+
+                if !text.is_empty() {
+                    items.push(Item::Text(std::mem::take(&mut text)));
+                }
+                items.push(Item::Variable(name));
+            }
+            '[' => {
+                if !text.is_empty() {
+                    items.push(Item::Text(std::mem::take(&mut text)));
+                }
+
+                let group = parse_items(chars, true)?;
+                let mut style = String::new();
+                if chars.peek() == Some(&'(') {
+                    chars.next();
+                    loop {
+                        match chars.next() {
+                            Some(')') => break,
+                            Some(c) => style.push(c),
+                            None => return Err("unclosed `(` in format style".to_string()),
+                        }
+                    }
+                }
+
+                items.push(Item::Group {
+                    items: group,
+                    style,
+                });
+            }
+            ']' if in_group => {
+                if !text.is_empty() {
+                    items.push(Item::Text(text));
+                }
+                return Ok(items);
+            }
+            c => text.push(c),
+        }
+    }
+
+    if in_group {
+        return Err("unclosed `[` in format".to_string());
+    }
+
+    if !text.is_empty() {
+        items.push(Item::Text(text));
+    }
+
+    Ok(items)
+}
+
+/// Parses a starship style string such as `bold fg:#ff0000 bg:blue`.
+///
+/// Unknown words are ignored, like starship does.
+pub fn parse_style(style: &str) -> Style {
+    let mut result = Style::new();
+
+    for word in style.split_whitespace() {
+        let word = word.to_lowercase();
+        match word.as_str() {
+            "bold" => result = result.bold(),
+            "italic" => result = result.italic(),
+            "underline" => result = result.underline(),
+            "dimmed" => result = result.dimmed(),
+            "inverted" => result = result.reverse(),
+            "blink" => result = result.blink(),
+            "hidden" => result = result.hidden(),
+            "strikethrough" => result = result.strikethrough(),
+            _ => {
+                if let Some(color) = word.strip_prefix("bg:") {
+                    if let Some(color) = parse_color(color) {
+                        result = result.on(color);
+                    }
+                } else if let Some(color) = parse_color(word.strip_prefix("fg:").unwrap_or(&word)) {
+                    result = result.fg(color);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Words of a style string other than colors.
+const STYLE_WORDS: &[&str] = &[
+    "bold",
+    "italic",
+    "underline",
+    "dimmed",
+    "inverted",
+    "blink",
+    "hidden",
+    "strikethrough",
+];
+
+/// Checks that every word of a style string is known, since `parse_style`
+/// ignores the rest.
+pub fn check_style(style: &str) -> Result<(), String> {
+    for word in style.split_whitespace() {
+        let word = word.to_lowercase();
+        let color = word
+            .strip_prefix("bg:")
+            .or_else(|| word.strip_prefix("fg:"))
+            .unwrap_or(&word);
+
+        if !STYLE_WORDS.contains(&word.as_str()) && parse_color(color).is_none() {
+            return Err(format!(
+                "unknown style `{word}`, expected one of: {}, a color name, a number from 0 to 255 or #rrggbb",
+                STYLE_WORDS.join(", ")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn parse_color(color: &str) -> Option<Color> {
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let r = u8::from_str_radix(hex.get(0..2)?, 16).ok()?;
+        let g = u8::from_str_radix(hex.get(2..4)?, 16).ok()?;
+        let b = u8::from_str_radix(hex.get(4..6)?, 16).ok()?;
+        return Some(Color::Rgb(r, g, b));
+    }
+
+    if let Ok(fixed) = color.parse::<u8>() {
+        return Some(Color::Fixed(fixed));
+    }
+
+    let color = match color {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "purple" | "magenta" => Color::Purple,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "bright-black" => Color::DarkGray,
+        "bright-red" => Color::LightRed,
+        "bright-green" => Color::LightGreen,
+        "bright-yellow" => Color::LightYellow,
+        "bright-blue" => Color::LightBlue,
+        "bright-purple" | "bright-magenta" => Color::LightPurple,
+        "bright-cyan" => Color::LightCyan,
+        "bright-white" => Color::LightGray,
+        _ => return None,
+    };
+
+    Some(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(name: &'static str, text: &str) -> Segment {
+        Segment {
+            name,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_expands_the_aliases() {
+        let format = Format::parse("[$symbol $branch](bold) $all_status$stashed").unwrap();
+
+        assert_eq!(
+            format.segments(),
+            [
+                "remote",
+                "branch",
+                "staged",
+                "unstaged",
+                "ignored",
+                "deleted",
+                "conflicted",
+                "stash"
+            ]
+        );
+    }
+
+    #[test]
+    fn single_status_counts_are_segments_of_their_own() {
+        let format = Format::parse("$modified $untracked").unwrap();
+        assert_eq!(format.segments(), ["modified", "untracked"]);
+
+        let segments = [
+            segment("unstaged", "?2 ~1"),
+            segment("modified", "~1"),
+            segment("untracked", "?2"),
+        ];
+        assert_eq!(format.render(&segments, false), "~1 ?2");
+    }
+
+    #[test]
+    fn parse_rejects_unclosed_groups() {
+        assert!(Format::parse("[$branch").is_err());
+        assert!(Format::parse("[$branch](bold").is_err());
+    }
+
+    #[test]
+    fn render_drops_empty_groups() {
+        let format = Format::parse("[on $branch ](bold)[\\[$stash\\]]($) done").unwrap();
+        let segments = [segment("branch", "main")];
+
+        assert_eq!(format.render(&segments, false), "on main  done");
+        assert_eq!(format.render(&[], false), " done");
+    }
+
+    #[test]
+    fn render_styles_groups_when_colored() {
+        let format = Format::parse("[$branch](red)").unwrap();
+        let segments = [segment("branch", "main")];
+
+        assert_eq!(
+            format.render(&segments, true),
+            Color::Red.paint("main").to_string()
+        );
+    }
+
+    #[test]
+    fn parse_style_reads_every_word() {
+        assert_eq!(
+            parse_style("Bold fg:#ff0000 bg:blue sparkly"),
+            Style::new()
+                .bold()
+                .fg(Color::Rgb(255, 0, 0))
+                .on(Color::Blue)
+        );
+        assert_eq!(parse_style(""), Style::new());
+    }
+
+    #[test]
+    fn check_style_rejects_unknown_words() {
+        assert!(check_style("bold bg:bright-cyan 208").is_ok());
+        assert!(check_style("sparkly").is_err());
+    }
+
+    #[test]
+    fn parse_color_reads_names_numbers_and_hex() {
+        assert_eq!(parse_color("magenta"), Some(Color::Purple));
+        assert_eq!(parse_color("bright-black"), Some(Color::DarkGray));
+        assert_eq!(parse_color("208"), Some(Color::Fixed(208)));
+        assert_eq!(parse_color("#0a0B0c"), Some(Color::Rgb(10, 11, 12)));
+
+        assert_eq!(parse_color("256"), None);
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("#ggggggg"), None);
+        // Six bytes, but not six hex digits.
+        assert_eq!(parse_color("#ééé"), None);
+    }
+}
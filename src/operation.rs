@@ -0,0 +1,322 @@
+use std::fs;
+use std::path::Path;
+
+use git2::{Repository, RepositoryState};
+
+/// Kind of operation in progress in the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+impl OperationKind {
+    /// Name of the operation as used in records and configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            OperationKind::Merge => "merge",
+            OperationKind::Rebase => "rebase",
+            OperationKind::CherryPick => "cherry-pick",
+            OperationKind::Revert => "revert",
+            OperationKind::Bisect => "bisect",
+            OperationKind::ApplyMailbox => "am",
+        }
+    }
+}
+
+/// Operation in progress, such as a merge or a rebase.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub kind: OperationKind,
+    /// Step being applied, for rebases.
+    pub step: Option<usize>,
+    /// Total number of steps, for rebases.
+    pub total: Option<usize>,
+    /// Entries left in the todo list of an interactive rebase.
+    pub todo: Option<usize>,
+    /// What is being applied: the branch being merged, or a short commit id.
+    pub source: Option<String>,
+    /// Subject of the commit being applied, for cherry-picks and reverts.
+    pub subject: Option<String>,
+    /// Revisions left to test, for bisects.
+    pub remaining: Option<usize>,
+}
+
+impl Operation {
+    /// Returns the operation in progress, or `None` when the repository is
+    /// in its normal state.
+    pub fn init(repo: &Repository) -> Option<Self> {
+        let kind = match repo.state() {
+            RepositoryState::Clean => return None,
+            RepositoryState::Merge => OperationKind::Merge,
+            RepositoryState::Revert | RepositoryState::RevertSequence => OperationKind::Revert,
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                OperationKind::CherryPick
+            }
+            RepositoryState::Bisect => OperationKind::Bisect,
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge
+            | RepositoryState::ApplyMailboxOrRebase => OperationKind::Rebase,
+            RepositoryState::ApplyMailbox => OperationKind::ApplyMailbox,
+        };
+
+        let mut operation = Self {
+            kind,
+            step: None,
+            total: None,
+            todo: None,
+            source: None,
+            subject: None,
+            remaining: None,
+        };
+
+        match kind {
+            OperationKind::Merge => operation.source = merge_source(repo),
+            OperationKind::CherryPick => {
+                operation.source = head_file_short_id(repo, "CHERRY_PICK_HEAD");
+                operation.subject = head_file_subject(repo, "CHERRY_PICK_HEAD");
+            }
+            OperationKind::Revert => {
+                operation.source = head_file_short_id(repo, "REVERT_HEAD");
+                operation.subject = head_file_subject(repo, "REVERT_HEAD");
+            }
+            OperationKind::Bisect => operation.remaining = bisect_remaining(repo),
+            _ => {}
+        }
+
+        if kind == OperationKind::Rebase || kind == OperationKind::ApplyMailbox {
+            let git_dir = repo.path();
+            let (step, total) = if git_dir.join("rebase-merge").is_dir() {
+                (
+                    read_number(&git_dir.join("rebase-merge/msgnum")),
+                    read_number(&git_dir.join("rebase-merge/end")),
+                )
+            } else {
+                (
+                    read_number(&git_dir.join("rebase-apply/next")),
+                    read_number(&git_dir.join("rebase-apply/last")),
+                )
+            };
+            operation.step = step;
+            operation.total = total;
+            operation.todo = count_todo(&git_dir.join("rebase-merge/git-rebase-todo"));
+        }
+
+        Some(operation)
+    }
+}
+
+impl Operation {
+    /// Rough number of bisect steps left, the way `git bisect` estimates it.
+    pub fn bisect_steps(&self) -> Option<usize> {
+        let remaining = self.remaining?;
+        if remaining < 2 {
+            return Some(0);
+        }
+
+        Some(remaining.ilog2() as usize)
+    }
+}
+
+fn read_number(path: &Path) -> Option<usize> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Counts the commands left in a rebase todo file, skipping comments.
+fn count_todo(path: &Path) -> Option<usize> {
+    let contents = fs::read_to_string(path).ok()?;
+    let count = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .count();
+
+    Some(count)
+}
+
+/// Branch being merged, from MERGE_MSG, falling back to the short id of
+/// MERGE_HEAD.
+fn merge_source(repo: &Repository) -> Option<String> {
+    if let Ok(message) = fs::read_to_string(repo.path().join("MERGE_MSG")) {
+        let first_line = message.lines().next().unwrap_or_default();
+        let mut quoted = first_line.split('\'');
+        if let (Some(_), Some(name)) = (quoted.next(), quoted.next()) {
+            if !name.is_empty() {
+                return Some(name.to_string());
+            }
+        }
+    }
+
+    head_file_short_id(repo, "MERGE_HEAD")
+}
+
+/// Short id of the first commit listed in a `*_HEAD` file of the git dir.
+fn head_file_short_id(repo: &Repository, name: &str) -> Option<String> {
+    let contents = fs::read_to_string(repo.path().join(name)).ok()?;
+    let id = contents.lines().next()?.trim().get(..7)?;
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some(id.to_string())
+}
+
+/// Subject of the commit listed in a `*_HEAD` file of the git dir.
+fn head_file_subject(repo: &Repository, name: &str) -> Option<String> {
+    let oid = repo.refname_to_id(name).ok()?;
+    let commit = repo.find_commit(oid).ok()?;
+    commit.summary().map(str::to_string)
+}
+
+/// Counts the commits reachable from `refs/bisect/bad` but from none of the
+/// `refs/bisect/good-*` refs.
+fn bisect_remaining(repo: &Repository) -> Option<usize> {
+    let bad = repo.refname_to_id("refs/bisect/bad").ok()?;
+
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.push(bad).ok()?;
+    for reference in repo.references_glob("refs/bisect/good-*").ok()?.flatten() {
+        if let Some(oid) = reference.target() {
+            revwalk.hide(oid).ok()?;
+        }
+    }
+
+    Some(revwalk.count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn clean_repositories_have_no_operation() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+
+        assert!(Operation::init(&repo.repo).is_none());
+    }
+
+    #[test]
+    fn merges_name_the_merged_branch() {
+        let repo = TempRepo::new();
+        repo.merge_conflict(&["a"], &[]);
+
+        let operation = Operation::init(&repo.repo).unwrap();
+        assert_eq!(operation.kind, OperationKind::Merge);
+        assert_eq!(operation.source.as_deref(), Some("theirs"));
+    }
+
+    #[test]
+    fn merges_fall_back_to_the_merge_head() {
+        let repo = TempRepo::new();
+        repo.merge_conflict(&["a"], &[]);
+        fs::write(repo.repo.path().join("MERGE_MSG"), "Merge\n").unwrap();
+        let theirs = repo.repo.refname_to_id("refs/heads/theirs").unwrap();
+
+        let operation = Operation::init(&repo.repo).unwrap();
+        assert_eq!(operation.source, Some(theirs.to_string()[..7].to_string()));
+    }
+
+    #[test]
+    fn rebases_read_their_progress() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let dir = repo.repo.path().join("rebase-merge");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("interactive"), "").unwrap();
+        fs::write(dir.join("msgnum"), "2\n").unwrap();
+        fs::write(dir.join("end"), "5\n").unwrap();
+        fs::write(
+            dir.join("git-rebase-todo"),
+            "pick 1234567 One\n\n# Commands:\nedit 89abcde Two\n",
+        )
+        .unwrap();
+
+        let operation = Operation::init(&repo.repo).unwrap();
+        assert_eq!(operation.kind, OperationKind::Rebase);
+        assert_eq!((operation.step, operation.total), (Some(2), Some(5)));
+        assert_eq!(operation.todo, Some(2));
+    }
+
+    #[test]
+    fn cherry_picks_read_the_picked_commit() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let picked = repo.commit_file("b", "b");
+        fs::write(
+            repo.repo.path().join("CHERRY_PICK_HEAD"),
+            format!("{picked}\n"),
+        )
+        .unwrap();
+
+        let operation = Operation::init(&repo.repo).unwrap();
+        assert_eq!(operation.kind, OperationKind::CherryPick);
+        assert_eq!(operation.source, Some(picked.to_string()[..7].to_string()));
+        assert_eq!(operation.subject.as_deref(), Some("Change b"));
+    }
+
+    #[test]
+    fn head_files_need_a_commit_id() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let head = repo.repo.path().join("CHERRY_PICK_HEAD");
+
+        for contents in ["abcdé0123\n", "ab\n", "not a commit id\n"] {
+            fs::write(&head, contents).unwrap();
+            let operation = Operation::init(&repo.repo).unwrap();
+            assert_eq!(operation.kind, OperationKind::CherryPick);
+            assert_eq!(operation.source, None);
+        }
+    }
+
+    #[test]
+    fn bisects_count_the_revisions_left() {
+        let repo = TempRepo::new();
+        let good = repo.commit_file("a", "1");
+        repo.commit_file("a", "2");
+        repo.commit_file("a", "3");
+        let bad = repo.commit_file("a", "4");
+        fs::write(repo.repo.path().join("BISECT_LOG"), "").unwrap();
+        repo.repo
+            .reference("refs/bisect/bad", bad, true, "test")
+            .unwrap();
+        repo.repo
+            .reference(&format!("refs/bisect/good-{good}"), good, true, "test")
+            .unwrap();
+
+        let operation = Operation::init(&repo.repo).unwrap();
+        assert_eq!(operation.kind, OperationKind::Bisect);
+        assert_eq!(operation.remaining, Some(3));
+        assert_eq!(operation.bisect_steps(), Some(1));
+    }
+
+    #[test]
+    fn bisect_steps_follow_git() {
+        let operation = |remaining| Operation {
+            kind: OperationKind::Bisect,
+            step: None,
+            total: None,
+            todo: None,
+            source: None,
+            subject: None,
+            remaining,
+        };
+
+        assert_eq!(operation(None).bisect_steps(), None);
+        assert_eq!(operation(Some(1)).bisect_steps(), Some(0));
+        assert_eq!(operation(Some(8)).bisect_steps(), Some(3));
+        assert_eq!(operation(Some(1000)).bisect_steps(), Some(9));
+    }
+
+    #[test]
+    fn names_match_the_records() {
+        assert_eq!(OperationKind::CherryPick.name(), "cherry-pick");
+        assert_eq!(OperationKind::ApplyMailbox.name(), "am");
+    }
+}
@@ -0,0 +1,240 @@
+//! Settings changed with `git_prompt config set`.
+//!
+//! They are saved as JSON in the user's config directory and applied on top
+//! of `$env.config.plugins.git_prompt`, so they survive restarts without
+//! touching the nushell config files.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use nu_protocol::{LabeledError, Record, Span, Value};
+
+use crate::config::config_dir;
+
+/// Path of the overrides file, `$XDG_CONFIG_HOME/nu_plugin_git_prompt/overrides.json`.
+pub fn path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("overrides.json"))
+}
+
+/// Reads the saved overrides, an empty record when there are none.
+pub fn load(span: Span) -> Result<Record, LabeledError> {
+    match path() {
+        Some(path) => load_from(&path, span),
+        None => Ok(Record::new()),
+    }
+}
+
+fn load_from(path: &Path, span: Span) -> Result<Record, LabeledError> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(Record::new());
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&text).map_err(|err| {
+        LabeledError::new("Invalid git_prompt overrides")
+            .with_label(format!("{}: {err}", path.display()), span)
+    })?;
+
+    match from_json(json, span) {
+        Value::Record { val, .. } => Ok(val.into_owned()),
+        _ => Ok(Record::new()),
+    }
+}
+
+/// Writes `overrides` to the overrides file.
+pub fn save(overrides: &Record, span: Span) -> Result<(), LabeledError> {
+    let path = path().ok_or_else(|| {
+        LabeledError::new("No config directory").with_label("HOME isn't set", span)
+    })?;
+
+    save_to(&path, overrides, span)
+}
+
+fn save_to(path: &Path, overrides: &Record, span: Span) -> Result<(), LabeledError> {
+    let json = to_json(&Value::record(overrides.clone(), span))?;
+    let write = |path: &Path| -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(
+            path,
+            serde_json::to_string_pretty(&json).unwrap_or_default(),
+        )
+    };
+
+    write(path).map_err(|err| {
+        LabeledError::new("Can't save the git_prompt overrides")
+            .with_label(format!("{}: {err}", path.display()), span)
+    })
+}
+
+/// Sets `value` at the dotted `key` of `record`, creating the records in
+/// between. A `nothing` value removes the key instead.
+pub fn set(record: &mut Record, key: &str, value: Value) {
+    let span = value.span();
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            if !matches!(record.get(head), Some(Value::Record { .. })) {
+                record.insert(head, Value::record(Record::new(), span));
+            }
+
+            if let Some(Value::Record { val, .. }) = record.get_mut(head) {
+                set(val.to_mut(), rest, value);
+            }
+        }
+        None if value.is_nothing() => {
+            record.remove(key);
+        }
+        None => {
+            record.insert(key, value);
+        }
+    }
+}
+
+/// Value at the dotted `key` of `record`.
+pub fn get<'a>(record: &'a Record, key: &str) -> Option<&'a Value> {
+    match key.split_once('.') {
+        Some((head, rest)) => match record.get(head)? {
+            Value::Record { val, .. } => get(val, rest),
+            _ => None,
+        },
+        None => record.get(key),
+    }
+}
+
+/// Merges `overrides` into `base`, recursing into records present in both.
+pub fn merge(base: &mut Record, overrides: &Record) {
+    for (key, value) in overrides.iter() {
+        match (base.get_mut(key), value) {
+            (Some(Value::Record { val: base, .. }), Value::Record { val, .. }) => {
+                merge(base.to_mut(), val);
+            }
+            _ => {
+                base.insert(key, value.clone());
+            }
+        }
+    }
+}
+
+fn from_json(json: serde_json::Value, span: Span) -> Value {
+    match json {
+        serde_json::Value::Null => Value::nothing(span),
+        serde_json::Value::Bool(bool) => Value::bool(bool, span),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(int) => Value::int(int, span),
+            None => Value::float(number.as_f64().unwrap_or_default(), span),
+        },
+        serde_json::Value::String(string) => Value::string(string, span),
+        serde_json::Value::Array(values) => Value::list(
+            values
+                .into_iter()
+                .map(|value| from_json(value, span))
+                .collect(),
+            span,
+        ),
+        serde_json::Value::Object(entries) => Value::record(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, from_json(value, span)))
+                .collect(),
+            span,
+        ),
+    }
+}
+
+fn to_json(value: &Value) -> Result<serde_json::Value, LabeledError> {
+    Ok(match value {
+        Value::Nothing { .. } => serde_json::Value::Null,
+        Value::Bool { val, .. } => serde_json::Value::Bool(*val),
+        Value::Int { val, .. } => serde_json::Value::from(*val),
+        Value::Float { val, .. } => serde_json::Value::from(*val),
+        Value::String { val, .. } => serde_json::Value::String(val.clone()),
+        Value::List { vals, .. } => {
+            serde_json::Value::Array(vals.iter().map(to_json).collect::<Result<_, _>>()?)
+        }
+        Value::Record { val, .. } => serde_json::Value::Object(
+            val.iter()
+                .map(|(key, value)| Ok((key.clone(), to_json(value)?)))
+                .collect::<Result<_, LabeledError>>()?,
+        ),
+        // Durations are stored as their seconds, which is how every
+        // duration setting reads an int.
+        Value::Duration { val, .. } => serde_json::Value::from(val / 1_000_000_000),
+        // Filesizes are stored as their bytes, which `max_repo_size` accepts.
+        Value::Filesize { val, .. } => serde_json::Value::from(val.get()),
+        value => {
+            return Err(LabeledError::new("Can't save this value").with_label(
+                format!("{} values can't be saved", value.get_type()),
+                value.span(),
+            ))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn set_makes_the_records_in_between() {
+        let mut overrides = Record::new();
+        set(&mut overrides, "divergence.ahead", Value::test_string("A"));
+        set(&mut overrides, "status_ttl", Value::test_int(5));
+        assert_eq!(
+            get(&overrides, "divergence.ahead"),
+            Some(&Value::test_string("A"))
+        );
+        assert_eq!(get(&overrides, "status_ttl"), Some(&Value::test_int(5)));
+
+        set(&mut overrides, "divergence.ahead", Value::test_nothing());
+        assert_eq!(get(&overrides, "divergence.ahead"), None);
+        assert!(get(&overrides, "divergence").is_some());
+    }
+
+    #[test]
+    fn merge_keeps_the_keys_it_doesnt_override() {
+        let mut base = record("color = true\n\n[divergence]\nahead = \"a\"\nbehind = \"b\"")
+            .into_record()
+            .unwrap();
+        let saved = record("color = false\n\n[divergence]\nbehind = \"B\"")
+            .into_record()
+            .unwrap();
+        merge(&mut base, &saved);
+
+        assert_eq!(get(&base, "color"), Some(&Value::test_bool(false)));
+        assert_eq!(
+            get(&base, "divergence.ahead"),
+            Some(&Value::test_string("a"))
+        );
+        assert_eq!(
+            get(&base, "divergence.behind"),
+            Some(&Value::test_string("B"))
+        );
+    }
+
+    #[test]
+    fn saved_overrides_read_back() {
+        let dir = TempRepo::new();
+        let path = dir.path().join("config/overrides.json");
+        let span = Span::test_data();
+        assert!(load_from(&path, span).unwrap().is_empty());
+
+        let mut overrides = Record::new();
+        set(&mut overrides, "theme", Value::test_string("ascii"));
+        set(
+            &mut overrides,
+            "status_ttl",
+            Value::test_duration(3_000_000_000),
+        );
+        set(&mut overrides, "stash.symbol", Value::test_string("$"));
+        save_to(&path, &overrides, span).unwrap();
+
+        let saved = load_from(&path, span).unwrap();
+        assert_eq!(get(&saved, "theme"), Some(&Value::test_string("ascii")));
+        assert_eq!(get(&saved, "status_ttl"), Some(&Value::test_int(3)));
+        assert_eq!(get(&saved, "stash.symbol"), Some(&Value::test_string("$")));
+
+        dir.write("config/overrides.json", "{ not json");
+        assert!(load_from(&path, span).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use nu_protocol::{record, Record, Span, Value};
+
+use crate::context::Context;
+use crate::operation::Operation;
+use crate::segments::{stash_count, tag, Segment};
+use crate::status::GitStatus;
+
+/// Everything the plugin knows about the repository as a nushell record.
+///
+/// This is what custom renderers receive, so they can build the prompt from
+/// the same data the built-in segments use.
+pub fn status_record(ctx: &Context, segments: &[Segment], span: Span) -> Value {
+    let head = ctx.head();
+    let (ahead, behind) = ctx.ahead_behind();
+    let (push_ahead, push_behind) = ctx.push_ahead_behind();
+    let status = ctx.status();
+
+    let count = |f: fn(&GitStatus) -> i64| -> Value { Value::int(status.map_or(0, f), span) };
+
+    Value::record(
+        record! {
+            "branch" => Value::string(&head.branch, span),
+            "tag" => Value::string(tag::describe(ctx, false).unwrap_or_default(), span),
+            "upstream" => Value::string(&head.remote, span),
+            "remote" => Value::string(&head.remote_name, span),
+            "upstream_gone" => Value::bool(head.upstream_gone, span),
+            "state" => Value::string(
+                ctx.operation().map_or("", |operation| operation.kind.name()),
+                span,
+            ),
+            "ahead" => Value::int(ahead.into(), span),
+            "behind" => Value::int(behind.into(), span),
+            "push_ahead" => Value::int(push_ahead.into(), span),
+            "push_behind" => Value::int(push_behind.into(), span),
+            "index_new" => count(|s| s.index_new.into()),
+            "index_modified" => count(|s| s.index_modified.into()),
+            "index_deleted" => count(|s| s.index_deleted.into()),
+            "index_renamed" => count(|s| s.index_renamed.into()),
+            "index_typechange" => count(|s| s.index_typechange.into()),
+            "wt_new" => count(|s| s.wt_new.into()),
+            "wt_modified" => count(|s| s.wt_modified.into()),
+            "wt_deleted" => count(|s| s.wt_deleted.into()),
+            "wt_renamed" => count(|s| s.wt_renamed.into()),
+            "wt_typechange" => count(|s| s.wt_typechange.into()),
+            "stashes" => Value::int(stash_count(ctx.repo) as i64, span),
+            "ignored" => count(|s| s.ignored.into()),
+            "conflicted" => count(|s| s.conflicted.into()),
+            "segments" => Value::record(
+                segments
+                    .iter()
+                    .map(|s| (s.name.to_string(), Value::string(&s.text, span)))
+                    .collect::<Record>(),
+                span,
+            ),
+        },
+        span,
+    )
+}
+
+/// The operation in progress as a nushell record, as returned by
+/// `git_prompt state`.
+pub fn operation_record(operation: &Operation, span: Span) -> Value {
+    let int = |value: Option<usize>| {
+        value.map_or(Value::nothing(span), |value| Value::int(value as i64, span))
+    };
+    let text = |value: &Option<String>| {
+        value
+            .as_ref()
+            .map_or(Value::nothing(span), |value| Value::string(value, span))
+    };
+
+    Value::record(
+        record! {
+            "kind" => Value::string(operation.kind.name(), span),
+            "step" => int(operation.step),
+            "total" => int(operation.total),
+            "todo" => int(operation.todo),
+            "source" => text(&operation.source),
+            "subject" => text(&operation.subject),
+            "remaining" => int(operation.remaining),
+        },
+        span,
+    )
+}
+
+/// One line of `git_prompt scan` and `git_prompt summary`: the branch, the
+/// change counts and the divergence of a repository.
+pub fn repo_row(ctx: &Context, span: Span) -> Value {
+    let head = ctx.head();
+    let (ahead, behind) = ctx.ahead_behind();
+    let status = ctx.status();
+
+    let count = |f: fn(&GitStatus) -> i64| -> Value { Value::int(status.map_or(0, f), span) };
+
+    Value::record(
+        record! {
+            "path" => Value::string(ctx.path.display().to_string(), span),
+            "branch" => Value::string(&head.branch, span),
+            // Summed as `i64`, the `u32` counts can add up past `u32::MAX`.
+            "staged" => count(|s| {
+                [
+                    s.index_new,
+                    s.index_modified,
+                    s.index_deleted,
+                    s.index_renamed,
+                    s.index_typechange,
+                ]
+                .into_iter()
+                .map(i64::from)
+                .sum()
+            }),
+            "unstaged" => count(|s| {
+                [s.wt_modified, s.wt_deleted, s.wt_renamed, s.wt_typechange]
+                    .into_iter()
+                    .map(i64::from)
+                    .sum()
+            }),
+            "untracked" => count(|s| s.wt_new.into()),
+            "conflicted" => count(|s| s.conflicted.into()),
+            "ahead" => Value::int(ahead.into(), span),
+            "behind" => Value::int(behind.into(), span),
+        },
+        span,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operation::OperationKind;
+    use crate::testing::TempRepo;
+
+    fn field<'a>(value: &'a Value, name: &str) -> &'a Value {
+        value.as_record().unwrap().get(name).unwrap()
+    }
+
+    #[test]
+    fn status_record_counts_the_changes() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.write("a", "b");
+        repo.write("b", "b");
+        repo.stage("b");
+        let ctx = Context::new(&repo.repo, repo.path());
+        let segments = [Segment {
+            name: "branch",
+            text: "main".to_string(),
+        }];
+
+        let status = status_record(&ctx, &segments, Span::test_data());
+        assert_eq!(field(&status, "branch"), &Value::test_string("main"));
+        assert_eq!(field(&status, "state"), &Value::test_string(""));
+        assert_eq!(field(&status, "index_new"), &Value::test_int(1));
+        assert_eq!(field(&status, "wt_modified"), &Value::test_int(1));
+        assert_eq!(field(&status, "stashes"), &Value::test_int(0));
+        assert_eq!(
+            field(&status, "segments"),
+            &Value::test_record(record! { "branch" => Value::test_string("main") })
+        );
+    }
+
+    #[test]
+    fn operation_record_leaves_unknown_fields_empty() {
+        let operation = Operation {
+            kind: OperationKind::Rebase,
+            step: Some(3),
+            total: Some(10),
+            todo: None,
+            source: Some("feature".to_string()),
+            subject: None,
+            remaining: None,
+        };
+
+        let record = operation_record(&operation, Span::test_data());
+        assert_eq!(field(&record, "kind"), &Value::test_string("rebase"));
+        assert_eq!(field(&record, "step"), &Value::test_int(3));
+        assert_eq!(field(&record, "total"), &Value::test_int(10));
+        assert_eq!(field(&record, "todo"), &Value::test_nothing());
+        assert_eq!(field(&record, "source"), &Value::test_string("feature"));
+        assert_eq!(field(&record, "subject"), &Value::test_nothing());
+    }
+
+    #[test]
+    fn repo_row_sums_the_counts_by_area() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.commit_file("b", "b");
+        repo.write("a", "changed");
+        repo.remove("b");
+        repo.write("c", "c");
+        repo.stage("c");
+        repo.write("d", "d");
+        let ctx = Context::new(&repo.repo, repo.path());
+
+        let row = repo_row(&ctx, Span::test_data());
+        assert_eq!(field(&row, "branch"), &Value::test_string("main"));
+        assert_eq!(field(&row, "staged"), &Value::test_int(1));
+        assert_eq!(field(&row, "unstaged"), &Value::test_int(2));
+        assert_eq!(field(&row, "untracked"), &Value::test_int(1));
+        assert_eq!(field(&row, "conflicted"), &Value::test_int(0));
+    }
+}
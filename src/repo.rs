@@ -0,0 +1,73 @@
+//! Opening repositories.
+//!
+//! libgit2's ownership check is a process-wide option, and the prompt turns it
+//! off to open repositories under `trusted_paths`. Plugin calls and the
+//! background threads open repositories concurrently, so every open goes
+//! through here, where none can run while the check is off.
+
+use std::path::Path;
+use std::sync::{PoisonError, RwLock};
+
+use git2::Repository;
+
+/// Held for reading by every open, and for writing while the ownership
+/// check is turned off.
+static OWNER_CHECK: RwLock<()> = RwLock::new(());
+
+/// Runs `open` with the ownership check on.
+pub fn checked<T>(open: impl FnOnce() -> T) -> T {
+    let _guard = OWNER_CHECK.read().unwrap_or_else(PoisonError::into_inner);
+    open()
+}
+
+/// Runs `open` with the ownership check off, for repositories the user
+/// trusts.
+pub fn unchecked(
+    open: impl FnOnce() -> Result<Repository, git2::Error>,
+) -> Result<Repository, git2::Error> {
+    let _guard = OWNER_CHECK.write().unwrap_or_else(PoisonError::into_inner);
+
+    // SAFETY: libgit2 doesn't synchronise its global options. Every open of
+    // the process holds `OWNER_CHECK` for reading, so while it's held for
+    // writing here no other thread opens a repository, nor reads or sets the
+    // option.
+    unsafe {
+        git2::opts::set_verify_owner_validation(false)?;
+        let repo = open();
+        git2::opts::set_verify_owner_validation(true)?;
+        repo
+    }
+}
+
+pub fn open(path: &Path) -> Result<Repository, git2::Error> {
+    checked(|| Repository::open(path))
+}
+
+pub fn discover(path: &Path) -> Result<Repository, git2::Error> {
+    checked(|| Repository::discover(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn discover_finds_the_repository_above() {
+        let repo = TempRepo::new();
+        repo.commit_file("src/main.rs", "fn main() {}");
+
+        let found = discover(&repo.path().join("src")).unwrap();
+        assert_eq!(found.path(), repo.repo.path());
+        assert!(open(&repo.path().join("src")).is_err());
+        assert!(open(repo.path()).is_ok());
+    }
+
+    #[test]
+    fn unchecked_opens_like_open() {
+        let repo = TempRepo::new();
+        assert!(unchecked(|| Repository::open(repo.path())).is_ok());
+        assert!(unchecked(|| Repository::open(repo.path().join("missing"))).is_err());
+        assert!(open(repo.path()).is_ok());
+    }
+}
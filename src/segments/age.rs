@@ -0,0 +1,105 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_seconds, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+use crate::time::{format_age, now};
+
+#[derive(Debug, Clone, Default)]
+pub struct AgeConfig {
+    pub symbol: String,
+    /// Hide the segment while HEAD is younger than this many seconds.
+    pub min_seconds: i64,
+}
+
+impl AgeConfig {
+    /// Keys of the `age` record.
+    pub const KEYS: &'static [&'static str] = &["symbol", "min_seconds"];
+
+    /// Applies the settings of the `age` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("age", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+        set_seconds(record, "min_seconds", &mut self.min_seconds)?;
+
+        Ok(())
+    }
+}
+
+/// Time since HEAD was committed, e.g. `3h`.
+pub struct Age {
+    config: AgeConfig,
+}
+
+impl Age {
+    pub fn new(config: AgeConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Age {
+    fn name(&self) -> &'static str {
+        "age"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let commit = ctx.repo.head().ok()?.peel_to_commit().ok()?;
+        let age = now() - commit.time().seconds();
+        if age < self.config.min_seconds {
+            return None;
+        }
+
+        Some(format!("{}{}", self.config.symbol, format_age(age)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = AgeConfig::default();
+        config
+            .update(&record("symbol = \"~\"\nmin_seconds = 90"))
+            .unwrap();
+
+        assert_eq!(config.symbol, "~");
+        assert_eq!(config.min_seconds, 90);
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = AgeConfig::default();
+        assert!(config.update(&record("symbols = \"~\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_age_of_head() {
+        let repo = TempRepo::new();
+        assert_eq!(repo.render(&Age::new(AgeConfig::default())), None);
+
+        repo.commit_file("a", "a");
+        let config = AgeConfig {
+            symbol: "~".to_string(),
+            min_seconds: 0,
+        };
+        let text = repo.render(&Age::new(config)).unwrap();
+        assert!(text.starts_with('~') && text.ends_with('s'), "{text}");
+    }
+
+    #[test]
+    fn hides_young_commits() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+
+        let config = AgeConfig {
+            min_seconds: 3_600,
+            ..AgeConfig::default()
+        };
+        assert_eq!(repo.render(&Age::new(config)), None);
+    }
+}
@@ -0,0 +1,265 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_string, set_usize};
+use crate::context::Context;
+use crate::segments::tag;
+use crate::segments::{Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct BranchConfig {
+    /// Show the most recent tag instead of the branch name when there is one.
+    pub prefer_tag: bool,
+    /// Only show a tag when HEAD is exactly at it.
+    pub exact_tag: bool,
+    /// Show `v1.2.3-4-gabc1234` instead of the commit id when detached.
+    pub describe_detached: bool,
+    /// List every tag pointing at HEAD instead of a single one.
+    pub all_head_tags: bool,
+    /// Most tags listed by `all_head_tags`, the rest is shown as `more_tags`.
+    pub max_tags: usize,
+    pub more_tags: String,
+    /// Put `annotated_symbol` or `lightweight_symbol` before the tag.
+    pub distinguish_tags: bool,
+    pub annotated_symbol: String,
+    pub lightweight_symbol: String,
+}
+
+impl Default for BranchConfig {
+    fn default() -> Self {
+        Self {
+            prefer_tag: true,
+            exact_tag: false,
+            describe_detached: false,
+            all_head_tags: false,
+            max_tags: 3,
+            more_tags: "…".to_string(),
+            distinguish_tags: false,
+            annotated_symbol: "\u{f02b}".to_string(),
+            lightweight_symbol: "\u{f02c}".to_string(),
+        }
+    }
+}
+
+impl BranchConfig {
+    /// Keys of the `branch` record.
+    pub const KEYS: &'static [&'static str] = &[
+        "prefer_tag",
+        "exact_tag",
+        "describe_detached",
+        "all_head_tags",
+        "max_tags",
+        "more_tags",
+        "distinguish_tags",
+        "annotated_symbol",
+        "lightweight_symbol",
+    ];
+
+    /// Applies the settings of the `branch` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("branch", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_bool(record, "prefer_tag", &mut self.prefer_tag)?;
+        set_bool(record, "exact_tag", &mut self.exact_tag)?;
+        set_bool(record, "describe_detached", &mut self.describe_detached)?;
+        set_bool(record, "all_head_tags", &mut self.all_head_tags)?;
+        set_usize(record, "max_tags", &mut self.max_tags)?;
+        set_string(record, "more_tags", &mut self.more_tags)?;
+        set_bool(record, "distinguish_tags", &mut self.distinguish_tags)?;
+        set_string(record, "annotated_symbol", &mut self.annotated_symbol)?;
+        set_string(record, "lightweight_symbol", &mut self.lightweight_symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Shows the branch name, the short commit id when detached, or the tag.
+pub struct Branch {
+    config: BranchConfig,
+}
+
+impl Branch {
+    pub fn new(config: BranchConfig) -> Self {
+        Self { config }
+    }
+
+    fn tag_label(&self, ctx: &Context, tag: String) -> String {
+        if !self.config.distinguish_tags {
+            return tag;
+        }
+
+        let symbol = if tag::is_annotated(ctx, &tag) {
+            &self.config.annotated_symbol
+        } else {
+            &self.config.lightweight_symbol
+        };
+
+        format!("{symbol} {tag}")
+    }
+}
+
+impl SegmentProvider for Branch {
+    fn name(&self) -> &'static str {
+        "branch"
+    }
+
+    fn cost(&self) -> Cost {
+        if self.config.prefer_tag || self.config.describe_detached {
+            Cost::Expensive
+        } else {
+            Cost::Cheap
+        }
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let head = ctx.repo.head().ok()?;
+        Some(format!(
+            "{}:{:?}:{}:{}",
+            head.name().unwrap_or_default(),
+            head.target(),
+            ctx.git_file_stamp("refs/tags"),
+            ctx.git_file_stamp("packed-refs"),
+        ))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        if self.config.describe_detached && ctx.repo.head_detached().unwrap_or(false) {
+            if let Some(label) = tag::describe_long(ctx) {
+                return Some(label);
+            }
+        }
+
+        if self.config.prefer_tag && self.config.all_head_tags {
+            let tags = tag::head_tags(ctx);
+            if !tags.is_empty() {
+                let mut labels: Vec<String> = tags
+                    .iter()
+                    .take(self.config.max_tags)
+                    .map(|tag| self.tag_label(ctx, tag.clone()))
+                    .collect();
+                if tags.len() > self.config.max_tags {
+                    labels.push(self.config.more_tags.clone());
+                }
+
+                return Some(labels.join(","));
+            }
+        }
+
+        if self.config.prefer_tag {
+            if let Some(tag) = tag::describe(ctx, self.config.exact_tag) {
+                return Some(self.tag_label(ctx, tag));
+            }
+        }
+
+        Some(ctx.head().branch.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = BranchConfig::default();
+        config
+            .update(&record(
+                "prefer_tag = false\nmax_tags = 1\nannotated_symbol = \"A\"",
+            ))
+            .unwrap();
+
+        assert!(!config.prefer_tag);
+        assert_eq!(config.max_tags, 1);
+        assert_eq!(config.annotated_symbol, "A");
+        assert_eq!(config.lightweight_symbol, "\u{f02c}");
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = BranchConfig::default();
+        assert!(config.update(&record("prefer_tag = \"yes\"")).is_err());
+        assert!(config.update(&record("max_tags = -1")).is_err());
+    }
+
+    #[test]
+    fn renders_the_branch_or_the_tag() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        assert_eq!(
+            repo.render(&Branch::new(BranchConfig::default()))
+                .as_deref(),
+            Some("main")
+        );
+
+        repo.tag("v1.0", true);
+        assert_eq!(
+            repo.render(&Branch::new(BranchConfig::default()))
+                .as_deref(),
+            Some("v1.0")
+        );
+
+        let config = BranchConfig {
+            prefer_tag: false,
+            ..BranchConfig::default()
+        };
+        assert_eq!(repo.render(&Branch::new(config)).as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn exact_tag_needs_head_at_the_tag() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.tag("v1.0", false);
+        repo.commit_file("a", "b");
+
+        let config = BranchConfig {
+            exact_tag: true,
+            ..BranchConfig::default()
+        };
+        assert_eq!(repo.render(&Branch::new(config)).as_deref(), Some("main"));
+        assert_eq!(
+            repo.render(&Branch::new(BranchConfig::default()))
+                .as_deref(),
+            Some("v1.0")
+        );
+    }
+
+    #[test]
+    fn joins_head_tags_with_their_kind() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.tag("a", true);
+        repo.tag("b", false);
+        repo.tag("c", false);
+
+        let config = BranchConfig {
+            all_head_tags: true,
+            max_tags: 2,
+            distinguish_tags: true,
+            annotated_symbol: "A".to_string(),
+            lightweight_symbol: "L".to_string(),
+            ..BranchConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Branch::new(config)).as_deref(),
+            Some("A a,L b,…")
+        );
+    }
+
+    #[test]
+    fn describes_a_detached_head() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.tag("v1.0", true);
+        let head = repo.commit_file("a", "b");
+        repo.repo.set_head_detached(head).unwrap();
+
+        let config = BranchConfig {
+            describe_detached: true,
+            ..BranchConfig::default()
+        };
+        let text = repo.render(&Branch::new(config)).unwrap();
+        assert_eq!(text, format!("v1.0-1-g{}", &head.to_string()[..7]));
+    }
+}
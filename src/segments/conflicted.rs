@@ -0,0 +1,179 @@
+use git2::IndexConflict;
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_string};
+use crate::context::Context;
+use crate::segments::{join_counts, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct ConflictedConfig {
+    pub symbol: String,
+    /// Split the count by conflict type, using the symbols below.
+    pub breakdown: bool,
+    pub both_modified: String,
+    pub deleted_by_us: String,
+    pub deleted_by_them: String,
+    pub added_by_both: String,
+}
+
+impl Default for ConflictedConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "c".to_string(),
+            breakdown: false,
+            both_modified: "UU".to_string(),
+            deleted_by_us: "DU".to_string(),
+            deleted_by_them: "UD".to_string(),
+            added_by_both: "AA".to_string(),
+        }
+    }
+}
+
+impl ConflictedConfig {
+    /// Keys of the `conflicted` record.
+    pub const KEYS: &'static [&'static str] = &[
+        "symbol",
+        "breakdown",
+        "both_modified",
+        "deleted_by_us",
+        "deleted_by_them",
+        "added_by_both",
+    ];
+
+    /// Applies the settings of the `conflicted` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("conflicted", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+        set_bool(record, "breakdown", &mut self.breakdown)?;
+        set_string(record, "both_modified", &mut self.both_modified)?;
+        set_string(record, "deleted_by_us", &mut self.deleted_by_us)?;
+        set_string(record, "deleted_by_them", &mut self.deleted_by_them)?;
+        set_string(record, "added_by_both", &mut self.added_by_both)?;
+
+        Ok(())
+    }
+}
+
+/// Count of files with merge conflicts.
+pub struct Conflicted {
+    config: ConflictedConfig,
+}
+
+impl Conflicted {
+    pub fn new(config: ConflictedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Conflicted {
+    fn name(&self) -> &'static str {
+        "conflicted"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        if self.config.breakdown {
+            return self.render_breakdown(ctx);
+        }
+
+        let status = ctx.status()?;
+
+        join_counts(&[(&self.config.symbol, status.conflicted)])
+    }
+}
+
+impl Conflicted {
+    /// Counts the conflicts of the index by which sides changed the file, as
+    /// in the short format of `git status`.
+    fn render_breakdown(&self, ctx: &Context) -> Option<String> {
+        let index = ctx.repo.index().ok()?;
+        if !index.has_conflicts() {
+            return None;
+        }
+
+        let mut both_modified: u32 = 0;
+        let mut deleted_by_us: u32 = 0;
+        let mut deleted_by_them: u32 = 0;
+        let mut added_by_both: u32 = 0;
+
+        for conflict in index.conflicts().ok()?.flatten() {
+            let IndexConflict {
+                ancestor,
+                our,
+                their,
+            } = conflict;
+
+            match (ancestor.is_some(), our.is_some(), their.is_some()) {
+                (true, true, true) => both_modified += 1,
+                (true, false, true) => deleted_by_us += 1,
+                (true, true, false) => deleted_by_them += 1,
+                (false, true, true) => added_by_both += 1,
+                _ => {}
+            }
+        }
+
+        join_counts(&[
+            (&self.config.both_modified, both_modified),
+            (&self.config.deleted_by_us, deleted_by_us),
+            (&self.config.deleted_by_them, deleted_by_them),
+            (&self.config.added_by_both, added_by_both),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = ConflictedConfig::default();
+        config
+            .update(&record("symbol = \"!\"\nbreakdown = true"))
+            .unwrap();
+
+        assert_eq!(config.symbol, "!");
+        assert!(config.breakdown);
+        assert_eq!(config.both_modified, "UU");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = ConflictedConfig::default();
+        assert!(config.update(&record("both = \"UU\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_conflict_count() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        assert_eq!(
+            repo.render(&Conflicted::new(ConflictedConfig::default())),
+            None
+        );
+
+        repo.merge_conflict(&["a", "b"], &["c"]);
+        assert_eq!(
+            repo.render(&Conflicted::new(ConflictedConfig::default()))
+                .as_deref(),
+            Some("c3")
+        );
+    }
+
+    #[test]
+    fn breaks_the_count_down_by_side() {
+        let repo = TempRepo::new();
+        repo.merge_conflict(&["a", "b"], &["c"]);
+
+        let config = ConflictedConfig {
+            breakdown: true,
+            ..ConflictedConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Conflicted::new(config)).as_deref(),
+            Some("UU2 AA1")
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use git2::Oid;
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{join_counts, Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct DefaultBranchConfig {
+    /// Remote whose `HEAD` names the default branch.
+    pub remote: String,
+    pub ahead: String,
+    pub behind: String,
+}
+
+impl Default for DefaultBranchConfig {
+    fn default() -> Self {
+        Self {
+            remote: "origin".to_string(),
+            ahead: "⇡".to_string(),
+            behind: "⇣".to_string(),
+        }
+    }
+}
+
+impl DefaultBranchConfig {
+    /// Keys of the `default_branch` record.
+    pub const KEYS: &'static [&'static str] = &["remote", "ahead", "behind"];
+
+    /// Applies the settings of the `default_branch` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("default_branch", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "remote", &mut self.remote)?;
+        set_string(record, "ahead", &mut self.ahead)?;
+        set_string(record, "behind", &mut self.behind)?;
+
+        Ok(())
+    }
+}
+
+/// Commits ahead of and behind the default branch of the remote, e.g.
+/// `⇡5 ⇣12 main`.
+pub struct DefaultBranch {
+    config: DefaultBranchConfig,
+}
+
+impl DefaultBranch {
+    pub fn new(config: DefaultBranchConfig) -> Self {
+        Self { config }
+    }
+
+    /// Short name and commit of `refs/remotes/<remote>/HEAD`.
+    fn target(&self, ctx: &Context) -> Option<(String, Oid)> {
+        let reference = ctx
+            .repo
+            .find_reference(&format!("refs/remotes/{}/HEAD", self.config.remote))
+            .ok()?
+            .resolve()
+            .ok()?;
+
+        let name = reference.shorthand()?;
+        let name = name
+            .strip_prefix(&format!("{}/", self.config.remote))
+            .unwrap_or(name)
+            .to_string();
+
+        Some((name, reference.target()?))
+    }
+}
+
+impl SegmentProvider for DefaultBranch {
+    fn name(&self) -> &'static str {
+        "default_branch"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let target = self.target(ctx).map(|(_, oid)| oid);
+        Some(format!("{:?}:{:?}", ctx.head().local, target))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let local = ctx.head().local?;
+        let (name, target) = self.target(ctx)?;
+
+        let (ahead, behind) = ctx.repo.graph_ahead_behind(local, target).ok()?;
+        let counts = join_counts(&[
+            (&self.config.ahead, ahead as u16),
+            (&self.config.behind, behind as u16),
+        ])?;
+
+        Some(format!("{counts} {name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = DefaultBranchConfig::default();
+        config
+            .update(&record("remote = \"upstream\"\nbehind = \"v\""))
+            .unwrap();
+
+        assert_eq!(config.remote, "upstream");
+        assert_eq!(config.ahead, "⇡");
+        assert_eq!(config.behind, "v");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = DefaultBranchConfig::default();
+        assert!(config.update(&record("branch = \"main\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_divergence_from_the_remote_head() {
+        let repo = TempRepo::new();
+        let base = repo.commit_file("a", "a");
+        let remote = repo.commit_file("b", "b");
+        repo.reset(base);
+        repo.commit_file("c", "c");
+        repo.commit_file("d", "d");
+        assert_eq!(
+            repo.render(&DefaultBranch::new(DefaultBranchConfig::default())),
+            None
+        );
+
+        repo.repo
+            .reference("refs/remotes/origin/trunk", remote, true, "test")
+            .unwrap();
+        repo.repo
+            .reference_symbolic(
+                "refs/remotes/origin/HEAD",
+                "refs/remotes/origin/trunk",
+                true,
+                "test",
+            )
+            .unwrap();
+        assert_eq!(
+            repo.render(&DefaultBranch::new(DefaultBranchConfig::default()))
+                .as_deref(),
+            Some("⇡2 ⇣1 trunk")
+        );
+    }
+
+    #[test]
+    fn hides_when_level_with_the_default_branch() {
+        let repo = TempRepo::new();
+        let head = repo.commit_file("a", "a");
+        repo.repo
+            .reference("refs/remotes/origin/main", head, true, "test")
+            .unwrap();
+        repo.repo
+            .reference_symbolic(
+                "refs/remotes/origin/HEAD",
+                "refs/remotes/origin/main",
+                true,
+                "test",
+            )
+            .unwrap();
+
+        assert_eq!(
+            repo.render(&DefaultBranch::new(DefaultBranchConfig::default())),
+            None
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{join_counts, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct DeletedConfig {
+    pub staged: String,
+    pub unstaged: String,
+}
+
+impl Default for DeletedConfig {
+    fn default() -> Self {
+        Self {
+            staged: "+-".to_string(),
+            unstaged: "-".to_string(),
+        }
+    }
+}
+
+impl DeletedConfig {
+    /// Keys of the `deleted` record.
+    pub const KEYS: &'static [&'static str] = &["staged", "unstaged"];
+
+    /// Applies the settings of the `deleted` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("deleted", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "staged", &mut self.staged)?;
+        set_string(record, "unstaged", &mut self.unstaged)?;
+
+        Ok(())
+    }
+}
+
+/// Counts of deleted files, staged and unstaged.
+pub struct Deleted {
+    config: DeletedConfig,
+}
+
+impl Deleted {
+    pub fn new(config: DeletedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Deleted {
+    fn name(&self) -> &'static str {
+        "deleted"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let status = ctx.status()?;
+
+        join_counts(&[
+            (&self.config.staged, status.index_deleted),
+            (&self.config.unstaged, status.wt_deleted),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = DeletedConfig::default();
+        config.update(&record("unstaged = \"✘\"")).unwrap();
+
+        assert_eq!(config.staged, "+-");
+        assert_eq!(config.unstaged, "✘");
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = DeletedConfig::default();
+        assert!(config.update(&record("staged = 1")).is_err());
+    }
+
+    #[test]
+    fn renders_staged_and_unstaged_deletions() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.commit_file("b", "b");
+        repo.commit_file("c", "c");
+        assert_eq!(repo.render(&Deleted::new(DeletedConfig::default())), None);
+
+        repo.remove("a");
+        repo.remove("b");
+        repo.unstage("b");
+        repo.remove("c");
+        repo.unstage("c");
+        assert_eq!(
+            repo.render(&Deleted::new(DeletedConfig::default()))
+                .as_deref(),
+            Some("+-2 -1")
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string, set_usize};
+use crate::context::Context;
+use crate::segments::{truncate, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct DescriptionConfig {
+    /// Longest description shown, in characters, before it is cut with `ellipsis`.
+    pub max_length: usize,
+    /// Ends a cut description.
+    pub ellipsis: String,
+}
+
+impl Default for DescriptionConfig {
+    fn default() -> Self {
+        Self {
+            max_length: 30,
+            ellipsis: "…".to_string(),
+        }
+    }
+}
+
+impl DescriptionConfig {
+    /// Keys of the `description` record.
+    pub const KEYS: &'static [&'static str] = &["max_length", "ellipsis"];
+
+    /// Applies the settings of the `description` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("description", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_usize(record, "max_length", &mut self.max_length)?;
+        set_string(record, "ellipsis", &mut self.ellipsis)?;
+
+        Ok(())
+    }
+}
+
+/// First line of the current branch's description, as set with
+/// `git branch --edit-description`.
+pub struct Description {
+    config: DescriptionConfig,
+}
+
+impl Description {
+    pub fn new(config: DescriptionConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Description {
+    fn name(&self) -> &'static str {
+        "description"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let branch = &ctx.head().branch;
+        let config = ctx.repo.config().ok()?;
+        let description = config
+            .get_string(&format!("branch.{branch}.description"))
+            .ok()?;
+
+        let line = description
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())?;
+
+        Some(truncate(
+            line,
+            self.config.max_length,
+            &self.config.ellipsis,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = DescriptionConfig::default();
+        config.update(&record("max_length = 5")).unwrap();
+
+        assert_eq!(config.max_length, 5);
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = DescriptionConfig::default();
+        assert!(config.update(&record("max_length = \"5\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_first_line_of_the_description() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        assert_eq!(
+            repo.render(&Description::new(DescriptionConfig::default())),
+            None
+        );
+
+        repo.repo
+            .config()
+            .unwrap()
+            .set_str(
+                "branch.main.description",
+                "\n  Rework the parser  \nDetails",
+            )
+            .unwrap();
+        assert_eq!(
+            repo.render(&Description::new(DescriptionConfig::default()))
+                .as_deref(),
+            Some("Rework the parser")
+        );
+
+        let config = DescriptionConfig {
+            max_length: 6,
+            ..DescriptionConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Description::new(config)).as_deref(),
+            Some("Rewor…")
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::diff;
+use crate::segments::{worktree_epoch, Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct DiffstatConfig {
+    pub insertions: String,
+    pub deletions: String,
+}
+
+impl Default for DiffstatConfig {
+    fn default() -> Self {
+        Self {
+            insertions: "+".to_string(),
+            deletions: "−".to_string(),
+        }
+    }
+}
+
+impl DiffstatConfig {
+    /// Keys of the `diffstat` record.
+    pub const KEYS: &'static [&'static str] = &["insertions", "deletions"];
+
+    /// Applies the settings of the `diffstat` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("diffstat", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "insertions", &mut self.insertions)?;
+        set_string(record, "deletions", &mut self.deletions)?;
+
+        Ok(())
+    }
+}
+
+/// Lines inserted and deleted by staged and unstaged changes together.
+///
+/// Much more expensive than the file counts, so it is not shown by default.
+pub struct Diffstat {
+    config: DiffstatConfig,
+    status_ttl: i64,
+}
+
+impl Diffstat {
+    pub fn new(config: DiffstatConfig, status_ttl: i64) -> Self {
+        Self { config, status_ttl }
+    }
+}
+
+impl SegmentProvider for Diffstat {
+    fn name(&self) -> &'static str {
+        "diffstat"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let epoch = worktree_epoch(self.status_ttl)?;
+        Some(format!("{}:{epoch}", ctx.status_key()))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let mut insertions = 0;
+        let mut deletions = 0;
+
+        for diff in [diff::staged(ctx.repo), diff::unstaged(ctx.repo)] {
+            let stats = diff.ok()?.stats().ok()?;
+            insertions += stats.insertions();
+            deletions += stats.deletions();
+        }
+
+        let mut parts = Vec::with_capacity(2);
+        if insertions > 0 {
+            parts.push(format!("{}{insertions}", self.config.insertions));
+        }
+
+        if deletions > 0 {
+            parts.push(format!("{}{deletions}", self.config.deletions));
+        }
+
+        if parts.is_empty() {
+            return None;
+        }
+
+        Some(parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = DiffstatConfig::default();
+        config.update(&record("deletions = \"-\"")).unwrap();
+
+        assert_eq!(config.insertions, "+");
+        assert_eq!(config.deletions, "-");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = DiffstatConfig::default();
+        assert!(config.update(&record("added = \"+\"")).is_err());
+    }
+
+    #[test]
+    fn renders_staged_and_unstaged_lines() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "1\n2\n3\n");
+        let diffstat = Diffstat::new(DiffstatConfig::default(), 0);
+        assert_eq!(repo.render(&diffstat), None);
+
+        repo.write("a", "1\nX\n3\n4\n");
+        assert_eq!(repo.render(&diffstat).as_deref(), Some("+2 −1"));
+
+        repo.write("b", "1\n2\n");
+        repo.stage("b");
+        assert_eq!(repo.render(&diffstat).as_deref(), Some("+4 −1"));
+    }
+
+    #[test]
+    fn cache_key_needs_a_status_ttl() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let ctx = Context::new(&repo.repo, repo.path());
+
+        assert_eq!(
+            Diffstat::new(DiffstatConfig::default(), 0).cache_key(&ctx),
+            None
+        );
+        assert!(Diffstat::new(DiffstatConfig::default(), 5)
+            .cache_key(&ctx)
+            .is_some());
+    }
+}
@@ -0,0 +1,293 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, invalid, lookup, section, set_string, set_style};
+use crate::context::Context;
+use crate::format::parse_style;
+use crate::segments::{Cost, SegmentProvider};
+
+/// Which ref the divergence is computed against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DivergenceTarget {
+    /// The upstream, `@{upstream}`.
+    #[default]
+    Upstream,
+    /// The push target, `@{push}`.
+    Push,
+    /// Both, the push divergence with its own symbols.
+    Both,
+}
+
+#[derive(Debug, Clone)]
+pub struct DivergenceConfig {
+    pub ahead: String,
+    pub behind: String,
+    pub push_ahead: String,
+    pub push_behind: String,
+    pub target: DivergenceTarget,
+    /// Behind count from which `behind_warning_style` is applied to it.
+    pub behind_warning: Option<u16>,
+    /// Starship-style style of a behind count over the warning threshold.
+    pub behind_warning_style: String,
+}
+
+impl Default for DivergenceConfig {
+    fn default() -> Self {
+        Self {
+            ahead: "↑".to_string(),
+            behind: "↓".to_string(),
+            push_ahead: "⇡".to_string(),
+            push_behind: "⇣".to_string(),
+            target: DivergenceTarget::default(),
+            behind_warning: None,
+            behind_warning_style: "bold red".to_string(),
+        }
+    }
+}
+
+impl DivergenceConfig {
+    /// Keys of the `divergence` record.
+    pub const KEYS: &'static [&'static str] = &[
+        "ahead",
+        "behind",
+        "push_ahead",
+        "push_behind",
+        "target",
+        "behind_warning",
+        "behind_warning_style",
+    ];
+
+    /// Applies the settings of the `divergence` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("divergence", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "ahead", &mut self.ahead)?;
+        set_string(record, "behind", &mut self.behind)?;
+        set_string(record, "push_ahead", &mut self.push_ahead)?;
+        set_string(record, "push_behind", &mut self.push_behind)?;
+
+        if let Some(target) = lookup(record, "target") {
+            self.target = match target.as_str() {
+                Ok("upstream") => DivergenceTarget::Upstream,
+                Ok("push") => DivergenceTarget::Push,
+                Ok("both") => DivergenceTarget::Both,
+                _ => {
+                    return Err(LabeledError::new("Invalid git_prompt setting `target`")
+                        .with_label("expected one of: upstream, push, both", target.span()))
+                }
+            };
+        }
+
+        if let Some(threshold) = lookup(record, "behind_warning") {
+            self.behind_warning = match threshold {
+                Value::Nothing { .. } => None,
+                value => Some(
+                    value
+                        .as_int()
+                        .ok()
+                        .and_then(|int| u16::try_from(int).ok())
+                        .ok_or_else(|| {
+                            invalid("behind_warning", "a number from 0 to 65535", value)
+                        })?,
+                ),
+            };
+        }
+
+        set_style(
+            record,
+            "behind_warning_style",
+            &mut self.behind_warning_style,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Commits ahead of and behind the upstream branch, the push target, or both.
+pub struct Divergence {
+    config: DivergenceConfig,
+    color: bool,
+}
+
+impl Divergence {
+    pub fn new(config: DivergenceConfig, color: bool) -> Self {
+        Self { config, color }
+    }
+
+    /// Joins the non-zero counts with a space, like `join_counts`, styling
+    /// the behind counts that reach the warning threshold.
+    fn join(&self, counts: &[(&str, u16, bool)]) -> Option<String> {
+        let parts: Vec<String> = counts
+            .iter()
+            .filter(|(_, count, _)| *count > 0)
+            .map(|(symbol, count, behind)| {
+                let text = format!("{symbol}{count}");
+                let warn = *behind
+                    && self.color
+                    && self
+                        .config
+                        .behind_warning
+                        .is_some_and(|threshold| *count >= threshold);
+
+                if warn {
+                    parse_style(&self.config.behind_warning_style)
+                        .paint(text)
+                        .to_string()
+                } else {
+                    text
+                }
+            })
+            .collect();
+
+        if parts.is_empty() {
+            return None;
+        }
+
+        Some(parts.join(" "))
+    }
+}
+
+impl SegmentProvider for Divergence {
+    fn name(&self) -> &'static str {
+        "divergence"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let head = ctx.head();
+        Some(format!(
+            "{:?}:{:?}:{:?}",
+            head.local, head.upstream, head.push
+        ))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        match self.config.target {
+            DivergenceTarget::Upstream => {
+                let (ahead, behind) = ctx.ahead_behind();
+                self.join(&[
+                    (&self.config.ahead, ahead, false),
+                    (&self.config.behind, behind, true),
+                ])
+            }
+            DivergenceTarget::Push => {
+                let (ahead, behind) = ctx.push_ahead_behind();
+                self.join(&[
+                    (&self.config.ahead, ahead, false),
+                    (&self.config.behind, behind, true),
+                ])
+            }
+            DivergenceTarget::Both => {
+                let (ahead, behind) = ctx.ahead_behind();
+                let (push_ahead, push_behind) = ctx.push_ahead_behind();
+                self.join(&[
+                    (&self.config.ahead, ahead, false),
+                    (&self.config.behind, behind, true),
+                    (&self.config.push_ahead, push_ahead, false),
+                    (&self.config.push_behind, push_behind, true),
+                ])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    /// Repository two commits ahead of and one behind its upstream.
+    fn diverged() -> TempRepo {
+        let repo = TempRepo::new();
+        let base = repo.commit_file("a", "a");
+        let remote = repo.commit_file("b", "b");
+        repo.reset(base);
+        repo.commit_file("c", "c");
+        repo.commit_file("d", "d");
+        repo.set_upstream("https://example.com/repo.git", remote);
+        repo
+    }
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = DivergenceConfig::default();
+        config
+            .update(&record(
+                "ahead = \"A\"\ntarget = \"both\"\nbehind_warning = 10",
+            ))
+            .unwrap();
+
+        assert_eq!(config.ahead, "A");
+        assert_eq!(config.target, DivergenceTarget::Both);
+        assert_eq!(config.behind_warning, Some(10));
+    }
+
+    #[test]
+    fn update_rejects_invalid_values() {
+        let mut config = DivergenceConfig::default();
+        assert!(config.update(&record("target = \"remote\"")).is_err());
+        assert!(config.update(&record("behind_warning = 70000")).is_err());
+        assert!(config
+            .update(&record("behind_warning_style = \"glowing\""))
+            .is_err());
+    }
+
+    #[test]
+    fn renders_the_upstream_counts() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let divergence = Divergence::new(DivergenceConfig::default(), false);
+        assert_eq!(repo.render(&divergence), None);
+
+        let repo = diverged();
+        assert_eq!(repo.render(&divergence).as_deref(), Some("↑2 ↓1"));
+    }
+
+    #[test]
+    fn renders_the_push_counts_with_their_symbols() {
+        let repo = diverged();
+        let base = repo.repo.revparse_single("main~2").unwrap().id();
+        repo.repo
+            .remote("fork", "https://example.com/fork.git")
+            .unwrap();
+        repo.repo
+            .reference("refs/remotes/fork/main", base, true, "test")
+            .unwrap();
+        repo.repo
+            .config()
+            .unwrap()
+            .set_str("branch.main.pushRemote", "fork")
+            .unwrap();
+
+        let config = DivergenceConfig {
+            target: DivergenceTarget::Both,
+            ..DivergenceConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Divergence::new(config, false)).as_deref(),
+            Some("↑2 ↓1 ⇡2")
+        );
+    }
+
+    #[test]
+    fn styles_behind_counts_over_the_warning() {
+        let repo = diverged();
+        let config = DivergenceConfig {
+            behind_warning: Some(1),
+            ..DivergenceConfig::default()
+        };
+
+        let styled = parse_style("bold red").paint("↓1").to_string();
+        assert_eq!(
+            repo.render(&Divergence::new(config.clone(), true)),
+            Some(format!("↑2 {styled}"))
+        );
+        assert_eq!(
+            repo.render(&Divergence::new(config, false)).as_deref(),
+            Some("↑2 ↓1")
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::time::UNIX_EPOCH;
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_seconds, set_string, set_style};
+use crate::context::Context;
+use crate::format::parse_style;
+use crate::segments::SegmentProvider;
+use crate::time::{format_age, now};
+
+#[derive(Debug, Clone)]
+pub struct FetchAgeConfig {
+    pub symbol: String,
+    /// Age in seconds above which the fetch counts as stale.
+    pub stale_seconds: i64,
+    /// Starship-style style applied once the fetch is stale.
+    pub stale_style: String,
+}
+
+impl Default for FetchAgeConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "⟳ ".to_string(),
+            stale_seconds: 7 * 86_400,
+            stale_style: "red".to_string(),
+        }
+    }
+}
+
+impl FetchAgeConfig {
+    /// Keys of the `fetch_age` record.
+    pub const KEYS: &'static [&'static str] = &["symbol", "stale_seconds", "stale_style"];
+
+    /// Applies the settings of the `fetch_age` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("fetch_age", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+        set_seconds(record, "stale_seconds", &mut self.stale_seconds)?;
+        set_style(record, "stale_style", &mut self.stale_style)?;
+
+        Ok(())
+    }
+}
+
+/// Time since the repository was last fetched, from the modification time of
+/// `FETCH_HEAD`, e.g. `⟳ 3d`.
+pub struct FetchAge {
+    config: FetchAgeConfig,
+    color: bool,
+}
+
+impl FetchAge {
+    pub fn new(config: FetchAgeConfig, color: bool) -> Self {
+        Self { config, color }
+    }
+}
+
+impl SegmentProvider for FetchAge {
+    fn name(&self) -> &'static str {
+        "fetch_age"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let fetched = std::fs::metadata(ctx.repo.path().join("FETCH_HEAD"))
+            .and_then(|metadata| metadata.modified())
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?;
+
+        let age = now() - fetched.as_secs() as i64;
+        let text = format!("{}{}", self.config.symbol, format_age(age));
+
+        if !self.color || age <= self.config.stale_seconds {
+            return Some(text);
+        }
+
+        Some(
+            parse_style(&self.config.stale_style)
+                .paint(text)
+                .to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    /// Writes `FETCH_HEAD` as if the last fetch was `seconds` ago.
+    fn fetched_ago(repo: &TempRepo, seconds: u64) {
+        let file = File::create(repo.repo.path().join("FETCH_HEAD")).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = FetchAgeConfig::default();
+        config
+            .update(&record("symbol = \"f\"\nstale_seconds = 60"))
+            .unwrap();
+
+        assert_eq!(config.symbol, "f");
+        assert_eq!(config.stale_seconds, 60);
+        assert_eq!(config.stale_style, "red");
+    }
+
+    #[test]
+    fn update_rejects_invalid_styles() {
+        let mut config = FetchAgeConfig::default();
+        assert!(config.update(&record("stale_style = \"#12\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_time_since_the_last_fetch() {
+        let repo = TempRepo::new();
+        let fetch_age = FetchAge::new(FetchAgeConfig::default(), true);
+        assert_eq!(repo.render(&fetch_age), None);
+
+        fetched_ago(&repo, 2 * 3_600 + 5);
+        assert_eq!(repo.render(&fetch_age).as_deref(), Some("⟳ 2h"));
+    }
+
+    #[test]
+    fn styles_stale_fetches() {
+        let repo = TempRepo::new();
+        fetched_ago(&repo, 8 * 86_400 + 5);
+
+        let styled = parse_style("red").paint("⟳ 8d").to_string();
+        assert_eq!(
+            repo.render(&FetchAge::new(FetchAgeConfig::default(), true)),
+            Some(styled)
+        );
+        assert_eq!(
+            repo.render(&FetchAge::new(FetchAgeConfig::default(), false))
+                .as_deref(),
+            Some("⟳ 8d")
+        );
+    }
+}
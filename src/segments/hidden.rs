@@ -0,0 +1,151 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{join_counts, Cost, SegmentProvider};
+
+/// `GIT_INDEX_ENTRY_VALID` in `flags`, set by `update-index --assume-unchanged`.
+const ASSUME_UNCHANGED: u16 = 0x8000;
+/// `GIT_INDEX_ENTRY_SKIP_WORKTREE` in `flags_extended`.
+const SKIP_WORKTREE: u16 = 0x4000;
+
+#[derive(Debug, Clone)]
+pub struct HiddenConfig {
+    pub skip_worktree_symbol: String,
+    pub assume_unchanged_symbol: String,
+}
+
+impl Default for HiddenConfig {
+    fn default() -> Self {
+        Self {
+            skip_worktree_symbol: "S".to_string(),
+            assume_unchanged_symbol: "A".to_string(),
+        }
+    }
+}
+
+impl HiddenConfig {
+    /// Keys of the `hidden` record.
+    pub const KEYS: &'static [&'static str] = &["skip_worktree_symbol", "assume_unchanged_symbol"];
+
+    /// Applies the settings of the `hidden` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("hidden", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(
+            record,
+            "skip_worktree_symbol",
+            &mut self.skip_worktree_symbol,
+        )?;
+        set_string(
+            record,
+            "assume_unchanged_symbol",
+            &mut self.assume_unchanged_symbol,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Count of index entries marked skip-worktree or assume-unchanged, whose
+/// changes don't show up in the status. It walks the whole index whenever it
+/// changes, so it isn't one of the default segments.
+pub struct Hidden {
+    config: HiddenConfig,
+}
+
+impl Hidden {
+    pub fn new(config: HiddenConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Hidden {
+    fn name(&self) -> &'static str {
+        "hidden"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        Some(ctx.git_file_stamp("index"))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let index = ctx.repo.index().ok()?;
+
+        // Sparse checkouts can mark far more entries than fit in a `u16`.
+        let mut skip_worktree: usize = 0;
+        let mut assume_unchanged: usize = 0;
+        for entry in index.iter() {
+            if entry.flags_extended & SKIP_WORKTREE != 0 {
+                skip_worktree += 1;
+            }
+
+            if entry.flags & ASSUME_UNCHANGED != 0 {
+                assume_unchanged += 1;
+            }
+        }
+
+        join_counts(&[
+            (&self.config.skip_worktree_symbol, skip_worktree),
+            (&self.config.assume_unchanged_symbol, assume_unchanged),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    /// Sets the index flags of `path` like `git update-index` does.
+    fn mark(repo: &TempRepo, path: &str, flags: u16, flags_extended: u16) {
+        let mut index = repo.repo.index().unwrap();
+        let mut entry = index.get_path(Path::new(path), 0).unwrap();
+        entry.flags |= flags;
+        entry.flags_extended |= flags_extended;
+        index.add(&entry).unwrap();
+        index.write().unwrap();
+    }
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = HiddenConfig::default();
+        config
+            .update(&record("skip_worktree_symbol = \"skip\""))
+            .unwrap();
+
+        assert_eq!(config.skip_worktree_symbol, "skip");
+        assert_eq!(config.assume_unchanged_symbol, "A");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = HiddenConfig::default();
+        assert!(config.update(&record("symbol = \"H\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_marked_entries() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.commit_file("b", "b");
+        repo.commit_file("c", "c");
+        assert_eq!(repo.render(&Hidden::new(HiddenConfig::default())), None);
+
+        mark(&repo, "a", 0, SKIP_WORKTREE);
+        mark(&repo, "b", 0, SKIP_WORKTREE);
+        mark(&repo, "c", ASSUME_UNCHANGED, 0);
+        assert_eq!(
+            repo.render(&Hidden::new(HiddenConfig::default()))
+                .as_deref(),
+            Some("S2 A1")
+        );
+    }
+}
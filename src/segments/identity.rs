@@ -0,0 +1,189 @@
+use nu_glob::Pattern;
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_optional_string, set_pairs, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct IdentityConfig {
+    /// Glob the effective `user.email` should match, e.g. `*@work.com`.
+    pub expected_email: Option<String>,
+    /// Pairs of repository path glob and expected email glob; the first pair
+    /// whose path matches the working tree overrides `expected_email`.
+    pub email_by_path: Vec<(String, String)>,
+    /// Shown when the email doesn't match.
+    pub mismatch: String,
+    /// Show the mismatched email after `mismatch`.
+    pub show_email: bool,
+    /// Shown when `user.name` or `user.email` is not set.
+    pub missing: String,
+}
+
+impl Default for IdentityConfig {
+    fn default() -> Self {
+        Self {
+            expected_email: None,
+            email_by_path: Vec::new(),
+            mismatch: "⚠".to_string(),
+            show_email: false,
+            missing: "⚠ no identity".to_string(),
+        }
+    }
+}
+
+impl IdentityConfig {
+    /// Keys of the `identity` record.
+    pub const KEYS: &'static [&'static str] = &[
+        "expected_email",
+        "email_by_path",
+        "mismatch",
+        "show_email",
+        "missing",
+    ];
+
+    /// Applies the settings of the `identity` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("identity", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_optional_string(record, "expected_email", &mut self.expected_email)?;
+        set_pairs(record, "email_by_path", &mut self.email_by_path)?;
+        set_string(record, "mismatch", &mut self.mismatch)?;
+        set_bool(record, "show_email", &mut self.show_email)?;
+        set_string(record, "missing", &mut self.missing)?;
+
+        Ok(())
+    }
+}
+
+/// Warns when the git identity of the repository looks wrong. Only the
+/// warning is shown unless `show_email` is set.
+pub struct Identity {
+    config: IdentityConfig,
+}
+
+impl Identity {
+    pub fn new(config: IdentityConfig) -> Self {
+        Self { config }
+    }
+
+    fn expected_email(&self, ctx: &Context) -> Option<&str> {
+        let workdir = ctx.repo.workdir().unwrap_or(ctx.repo.path());
+        let workdir = workdir.to_string_lossy();
+        let workdir = workdir.trim_end_matches('/');
+
+        self.config
+            .email_by_path
+            .iter()
+            .find(|(path, _)| Pattern::new(path).is_ok_and(|path| path.matches(workdir)))
+            .map(|(_, email)| email.as_str())
+            .or(self.config.expected_email.as_deref())
+    }
+}
+
+impl SegmentProvider for Identity {
+    fn name(&self) -> &'static str {
+        "identity"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let config = ctx.repo.config().ok()?;
+        let name = config.get_string("user.name").unwrap_or_default();
+        let email = config.get_string("user.email").unwrap_or_default();
+        if name.is_empty() || email.is_empty() {
+            return Some(self.config.missing.clone());
+        }
+
+        let expected = self.expected_email(ctx)?;
+
+        let matches = Pattern::new(&expected.to_lowercase())
+            .is_ok_and(|pattern| pattern.matches(&email.to_lowercase()));
+        if matches {
+            return None;
+        }
+
+        if !self.config.show_email {
+            return Some(self.config.mismatch.clone());
+        }
+
+        Some(format!("{} {email}", self.config.mismatch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = IdentityConfig::default();
+        config
+            .update(&record(
+                "expected_email = \"*@work.com\"\n[email_by_path]\n\"*/oss/*\" = \"*@home.org\"",
+            ))
+            .unwrap();
+
+        assert_eq!(config.expected_email.as_deref(), Some("*@work.com"));
+        assert_eq!(
+            config.email_by_path,
+            vec![("*/oss/*".to_string(), "*@home.org".to_string())]
+        );
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = IdentityConfig::default();
+        assert!(config.update(&record("email_by_path = \"*\"")).is_err());
+    }
+
+    #[test]
+    fn is_a_default_segment() {
+        assert!(crate::config::DEFAULT_SEGMENTS.contains(&"identity"));
+    }
+
+    #[test]
+    fn renders_a_missing_identity() {
+        let repo = TempRepo::new();
+        let identity = Identity::new(IdentityConfig::default());
+        assert_eq!(repo.render(&identity), None);
+
+        repo.repo
+            .config()
+            .unwrap()
+            .set_str("user.email", "")
+            .unwrap();
+        assert_eq!(repo.render(&identity).as_deref(), Some("⚠ no identity"));
+    }
+
+    #[test]
+    fn renders_an_unexpected_email() {
+        let repo = TempRepo::new();
+        let config = IdentityConfig {
+            expected_email: Some("*@EXAMPLE.com".to_string()),
+            ..IdentityConfig::default()
+        };
+        assert_eq!(repo.render(&Identity::new(config)), None);
+
+        let config = IdentityConfig {
+            expected_email: Some("*@example.com".to_string()),
+            email_by_path: vec![("/*".to_string(), "*@work.com".to_string())],
+            ..IdentityConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Identity::new(config.clone())).as_deref(),
+            Some("⚠")
+        );
+
+        let config = IdentityConfig {
+            show_email: true,
+            ..config
+        };
+        assert_eq!(
+            repo.render(&Identity::new(config)).as_deref(),
+            Some("⚠ test@example.com")
+        );
+    }
+}
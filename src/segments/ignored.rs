@@ -0,0 +1,93 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{join_counts, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct IgnoredConfig {
+    pub symbol: String,
+}
+
+impl Default for IgnoredConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "!".to_string(),
+        }
+    }
+}
+
+impl IgnoredConfig {
+    /// Keys of the `ignored` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `ignored` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("ignored", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Count of ignored files.
+pub struct Ignored {
+    config: IgnoredConfig,
+}
+
+impl Ignored {
+    pub fn new(config: IgnoredConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Ignored {
+    fn name(&self) -> &'static str {
+        "ignored"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let status = ctx.status()?;
+
+        join_counts(&[(&self.config.symbol, status.ignored)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::GitStatus;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = IgnoredConfig::default();
+        config.update(&record("symbol = \"◌\"")).unwrap();
+
+        assert_eq!(config.symbol, "◌");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = IgnoredConfig::default();
+        assert!(config.update(&record("enabled = true")).is_err());
+    }
+
+    #[test]
+    fn renders_the_ignored_count() {
+        let repo = TempRepo::new();
+        let ctx = Context::new(&repo.repo, repo.path());
+        let ignored = Ignored::new(IgnoredConfig::default());
+        assert_eq!(ignored.render(&ctx), None);
+
+        // The status scan leaves out ignored files, so the count is given.
+        let ctx = Context::new(&repo.repo, repo.path());
+        ctx.set_status(GitStatus {
+            ignored: 2,
+            ..GitStatus::default()
+        });
+        assert_eq!(ignored.render(&ctx).as_deref(), Some("!2"));
+    }
+}
@@ -0,0 +1,259 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct LfsLocksConfig {
+    pub symbol: String,
+}
+
+impl Default for LfsLocksConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "L".to_string(),
+        }
+    }
+}
+
+impl LfsLocksConfig {
+    /// Keys of the `lfs_locks` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `lfs_locks` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("lfs_locks", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Number of files locked by the current user through Git LFS, read from the
+/// local lock cache so no request is made to the LFS server and `git lfs`
+/// isn't needed.
+pub struct LfsLocks {
+    config: LfsLocksConfig,
+}
+
+impl LfsLocks {
+    pub fn new(config: LfsLocksConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for LfsLocks {
+    fn name(&self) -> &'static str {
+        "lfs_locks"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        Some(ctx.git_file_stamp("lfs/lockcache.db"))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let count = lfs_lock_count(ctx)?;
+        if count == 0 {
+            return None;
+        }
+
+        Some(format!("{}{count}", self.config.symbol))
+    }
+}
+
+/// First type id of the types a gob stream defines itself, the lower ones
+/// being predefined.
+const GOB_FIRST_USER_TYPE: i64 = 64;
+
+/// Number of locks in `lfs/lockcache.db`, what `git lfs locks --local` lists.
+///
+/// The cache is a Go gob stream holding the version of the store and then a
+/// map in which every lock is stored twice, by path and by id. Only the length
+/// of the map is read, the locks themselves aren't decoded.
+fn lfs_lock_count(ctx: &Context) -> Option<usize> {
+    let data = std::fs::read(ctx.repo.path().join("lfs/lockcache.db")).ok()?;
+    let mut gob = Gob {
+        data: &data,
+        pos: 0,
+    };
+
+    loop {
+        let len = usize::try_from(gob.uint()?).ok()?;
+        let end = gob.pos.checked_add(len)?;
+
+        // Messages with a negative id define a type, and the version is a
+        // predefined int.
+        if gob.int()? >= GOB_FIRST_USER_TYPE {
+            // A value that isn't a struct is sent as a struct with a single
+            // field, so its field delta comes first.
+            gob.uint()?;
+            let entries = usize::try_from(gob.uint()?).ok()?;
+            return Some(entries / 2);
+        }
+
+        gob.pos = end;
+    }
+}
+
+/// Reader of the numbers of a gob stream.
+struct Gob<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Gob<'_> {
+    /// Values under 128 take a single byte, larger ones are big-endian
+    /// after a byte holding their negated length.
+    fn uint(&mut self) -> Option<u64> {
+        let first = *self.data.get(self.pos)?;
+        self.pos += 1;
+        if first < 0x80 {
+            return Some(u64::from(first));
+        }
+
+        let len = usize::from(first.wrapping_neg());
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        if len > 8 {
+            return None;
+        }
+
+        Some(bytes.iter().fold(0, |n, byte| n << 8 | u64::from(*byte)))
+    }
+
+    /// The low bit of the uint holds the sign, complementing the rest.
+    fn int(&mut self) -> Option<i64> {
+        let n = self.uint()?;
+        let value = (n >> 1) as i64;
+        Some(if n & 1 == 1 { !value } else { value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    fn uint(n: u64) -> Vec<u8> {
+        if n < 0x80 {
+            return vec![n as u8];
+        }
+
+        let bytes: Vec<u8> = n
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|byte| *byte == 0)
+            .collect();
+        let mut encoded = vec![(bytes.len() as u8).wrapping_neg()];
+        encoded.extend(bytes);
+        encoded
+    }
+
+    fn int(n: i64) -> Vec<u8> {
+        if n < 0 {
+            uint((!n as u64) << 1 | 1)
+        } else {
+            uint((n as u64) << 1)
+        }
+    }
+
+    fn message(type_id: i64, payload: &[u8]) -> Vec<u8> {
+        let body = [int(type_id), payload.to_vec()].concat();
+        [uint(body.len() as u64), body].concat()
+    }
+
+    /// Lock cache like `git lfs` writes it: the version, the type of the
+    /// map, then the map holding every lock by path and by id.
+    fn lockcache(locks: u64) -> Vec<u8> {
+        let mut map = vec![0];
+        map.extend(uint(locks * 2));
+        for _ in 0..locks * 2 {
+            map.extend([1, b'k', 1, b'v']);
+        }
+
+        [
+            message(2, &[0, 2]),
+            message(-65, &[1, 2, 3]),
+            message(65, &map),
+        ]
+        .concat()
+    }
+
+    fn write_lockcache(repo: &TempRepo, data: &[u8]) {
+        let dir = repo.repo.path().join("lfs");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("lockcache.db"), data).unwrap();
+    }
+
+    #[test]
+    fn gob_reads_numbers() {
+        let data = [uint(5), uint(300), uint(u64::MAX), int(-65), int(64)].concat();
+        let mut gob = Gob {
+            data: &data,
+            pos: 0,
+        };
+
+        assert_eq!(gob.uint(), Some(5));
+        assert_eq!(gob.uint(), Some(300));
+        assert_eq!(gob.uint(), Some(u64::MAX));
+        assert_eq!(gob.int(), Some(-65));
+        assert_eq!(gob.int(), Some(64));
+        assert_eq!(gob.uint(), None);
+    }
+
+    #[test]
+    fn gob_rejects_truncated_numbers() {
+        let data = [0xfe, 0x01];
+        let mut gob = Gob {
+            data: &data,
+            pos: 0,
+        };
+
+        assert_eq!(gob.uint(), None);
+    }
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = LfsLocksConfig::default();
+        config.update(&record("symbol = \"🔐\"")).unwrap();
+
+        assert_eq!(config.symbol, "🔐");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = LfsLocksConfig::default();
+        assert!(config.update(&record("remote = true")).is_err());
+    }
+
+    #[test]
+    fn renders_the_cached_lock_count() {
+        let repo = TempRepo::new();
+        let lfs_locks = LfsLocks::new(LfsLocksConfig::default());
+        assert_eq!(repo.render(&lfs_locks), None);
+
+        write_lockcache(&repo, &lockcache(0));
+        assert_eq!(repo.render(&lfs_locks), None);
+
+        write_lockcache(&repo, &lockcache(3));
+        assert_eq!(repo.render(&lfs_locks).as_deref(), Some("L3"));
+    }
+
+    #[test]
+    fn ignores_a_truncated_cache() {
+        let repo = TempRepo::new();
+        let data = lockcache(3);
+        write_lockcache(&repo, &data[..data.len() / 3]);
+
+        assert_eq!(repo.render(&LfsLocks::new(LfsLocksConfig::default())), None);
+    }
+}
@@ -0,0 +1,91 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct LockConfig {
+    pub symbol: String,
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "🔒".to_string(),
+        }
+    }
+}
+
+impl LockConfig {
+    /// Keys of the `lock` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `lock` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("lock", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Shown while `index.lock` exists: another git process is running, or a
+/// stale lock was left behind.
+pub struct Lock {
+    config: LockConfig,
+}
+
+impl Lock {
+    pub fn new(config: LockConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Lock {
+    fn name(&self) -> &'static str {
+        "lock"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        if !ctx.index_locked() {
+            return None;
+        }
+
+        Some(self.config.symbol.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = LockConfig::default();
+        config.update(&record("symbol = \"L\"")).unwrap();
+
+        assert_eq!(config.symbol, "L");
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = LockConfig::default();
+        assert!(config.update(&record("symbol = false")).is_err());
+    }
+
+    #[test]
+    fn renders_while_the_index_is_locked() {
+        let repo = TempRepo::new();
+        let lock = Lock::new(LockConfig::default());
+        assert_eq!(repo.render(&lock), None);
+
+        fs::write(repo.repo.path().join("index.lock"), "").unwrap();
+        assert_eq!(repo.render(&lock).as_deref(), Some("🔒"));
+    }
+}
@@ -0,0 +1,100 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+/// Files that exist while `git gc` or `git maintenance` runs, relative to the
+/// common git dir.
+const LOCK_FILES: &[&str] = &["gc.pid", "objects/maintenance.lock"];
+
+#[derive(Debug, Clone)]
+pub struct MaintenanceConfig {
+    pub symbol: String,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "⚙".to_string(),
+        }
+    }
+}
+
+impl MaintenanceConfig {
+    /// Keys of the `maintenance` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `maintenance` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("maintenance", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Shown while garbage collection or background maintenance runs, which
+/// explains a slow prompt and slow git commands.
+pub struct Maintenance {
+    config: MaintenanceConfig,
+}
+
+impl Maintenance {
+    pub fn new(config: MaintenanceConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Maintenance {
+    fn name(&self) -> &'static str {
+        "maintenance"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let common_dir = ctx.repo.commondir();
+        if !LOCK_FILES.iter().any(|file| common_dir.join(file).exists()) {
+            return None;
+        }
+
+        Some(self.config.symbol.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = MaintenanceConfig::default();
+        config.update(&record("symbol = \"gc\"")).unwrap();
+
+        assert_eq!(config.symbol, "gc");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = MaintenanceConfig::default();
+        assert!(config.update(&record("gc = \"⚙\"")).is_err());
+    }
+
+    #[test]
+    fn renders_while_a_lock_file_exists() {
+        let repo = TempRepo::new();
+        let maintenance = Maintenance::new(MaintenanceConfig::default());
+        assert_eq!(repo.render(&maintenance), None);
+
+        for file in LOCK_FILES {
+            let path = repo.repo.path().join(file);
+            fs::write(&path, "").unwrap();
+            assert_eq!(repo.render(&maintenance).as_deref(), Some("⚙"));
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
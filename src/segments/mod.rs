@@ -0,0 +1,343 @@
+mod age;
+mod branch;
+mod conflicted;
+mod default_branch;
+mod deleted;
+mod description;
+mod diffstat;
+mod divergence;
+mod fetch_age;
+mod hidden;
+mod identity;
+mod ignored;
+mod lfs_locks;
+mod lock;
+mod maintenance;
+mod promisor;
+mod rel_path;
+mod remote;
+mod remotes;
+mod shallow;
+mod signature;
+mod staged;
+mod stash;
+mod state;
+mod subject;
+mod submodules;
+mod superproject;
+pub mod tag;
+mod unpublished;
+mod unpushed;
+mod unstaged;
+mod version;
+mod worktree;
+
+pub use age::{Age, AgeConfig};
+pub use branch::{Branch, BranchConfig};
+pub use conflicted::{Conflicted, ConflictedConfig};
+pub use default_branch::{DefaultBranch, DefaultBranchConfig};
+pub use deleted::{Deleted, DeletedConfig};
+pub use description::{Description, DescriptionConfig};
+pub use diffstat::{Diffstat, DiffstatConfig};
+pub use divergence::{Divergence, DivergenceConfig};
+pub use fetch_age::{FetchAge, FetchAgeConfig};
+pub use hidden::{Hidden, HiddenConfig};
+pub use identity::{Identity, IdentityConfig};
+pub use ignored::{Ignored, IgnoredConfig};
+pub use lfs_locks::{LfsLocks, LfsLocksConfig};
+pub use lock::{Lock, LockConfig};
+pub use maintenance::{Maintenance, MaintenanceConfig};
+pub use promisor::{Promisor, PromisorConfig};
+pub use rel_path::{RelPath, RelPathConfig};
+pub use remote::{Remote, RemoteConfig};
+pub use remotes::{Remotes, RemotesConfig};
+pub use shallow::{Shallow, ShallowConfig};
+pub use signature::{Signature, SignatureConfig};
+pub use staged::{Staged, StagedConfig};
+pub use stash::{stash_count, Stash, StashConfig};
+pub use state::{State, StateConfig};
+pub use subject::{Subject, SubjectConfig};
+pub use submodules::{Submodules, SubmodulesConfig};
+pub use superproject::{Superproject, SuperprojectConfig};
+pub use unpublished::{Unpublished, UnpublishedConfig};
+pub use unpushed::{Unpushed, UnpushedConfig};
+pub use unstaged::{Unstaged, UnstagedConfig, UnstagedCount};
+pub use version::{Version, VersionConfig};
+pub use worktree::{Worktree, WorktreeConfig};
+
+use std::fmt::Display;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::cache::SegmentCache;
+use crate::config::Config;
+use crate::context::Context;
+use crate::time::now;
+
+/// A single piece of the prompt, such as the branch name or the staged counts.
+///
+/// Each provider lives in its own module together with its config struct, and
+/// only reads the repository through the shared [`Context`].
+pub trait SegmentProvider {
+    /// Name used to refer to the segment in configuration.
+    fn name(&self) -> &'static str;
+
+    /// How costly the segment is to compute.
+    fn cost(&self) -> Cost {
+        Cost::Cheap
+    }
+
+    /// Fingerprint of everything the output depends on.
+    ///
+    /// Only used for [`Cost::Expensive`] segments: when the key matches the
+    /// one from the previous render of the same repository, the previous
+    /// output is reused. `None` means the segment is computed every time.
+    fn cache_key(&self, _ctx: &Context) -> Option<String> {
+        None
+    }
+
+    /// Renders the segment, or `None` when there is nothing to show.
+    fn render(&self, ctx: &Context) -> Option<String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cost {
+    /// Computed on every render.
+    Cheap,
+    /// Recomputed only when its cache key changes.
+    Expensive,
+}
+
+/// Rendered output of one provider.
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub name: &'static str,
+    pub text: String,
+}
+
+/// Ordered set of segment providers making up the prompt.
+pub struct Registry {
+    providers: Vec<Box<dyn SegmentProvider>>,
+    /// Fingerprint of the settings the providers were built with, part of
+    /// every cache key so changed settings show up on the next render.
+    settings: String,
+}
+
+impl Registry {
+    /// Builds the providers named in `segments`, in that order.
+    pub fn new(config: &Config, segments: &[String]) -> Self {
+        let mut available: Vec<Option<Box<dyn SegmentProvider>>> =
+            providers(config).into_iter().map(Some).collect();
+        let providers = segments
+            .iter()
+            .filter_map(|name| {
+                available
+                    .iter_mut()
+                    .find(|provider| provider.as_ref().is_some_and(|p| p.name() == name))
+                    .and_then(Option::take)
+            })
+            .collect();
+
+        let mut hasher = DefaultHasher::new();
+        format!("{config:?}").hash(&mut hasher);
+
+        Self {
+            providers,
+            settings: format!("{:x}", hasher.finish()),
+        }
+    }
+
+    /// Renders every provider in order, skipping the empty ones.
+    pub fn render(&self, ctx: &Context, cache: &SegmentCache) -> Vec<Segment> {
+        self.providers
+            .iter()
+            .filter_map(|provider| {
+                let text = render_cached(provider.as_ref(), ctx, cache, &self.settings)?;
+                (!text.is_empty()).then_some(Segment {
+                    name: provider.name(),
+                    text,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Every provider, configured with `config`.
+fn providers(config: &Config) -> Vec<Box<dyn SegmentProvider>> {
+    vec![
+        Box::new(Remote::new(config.remote.clone())),
+        Box::new(Branch::new(config.branch.clone())),
+        Box::new(State::new(config.state.clone(), config.color)),
+        Box::new(Staged::new(config.staged.clone())),
+        Box::new(Unstaged::new(config.unstaged.clone())),
+        Box::new(UnstagedCount::modified(&config.unstaged)),
+        Box::new(UnstagedCount::untracked(&config.unstaged)),
+        Box::new(UnstagedCount::renamed(&config.unstaged)),
+        Box::new(Divergence::new(config.divergence.clone(), config.color)),
+        Box::new(Stash::new(config.stash.clone())),
+        Box::new(DefaultBranch::new(config.default_branch.clone())),
+        Box::new(Ignored::new(config.ignored.clone())),
+        Box::new(Deleted::new(config.deleted.clone())),
+        Box::new(Conflicted::new(config.conflicted.clone())),
+        Box::new(RelPath::new(config.rel_path.clone())),
+        Box::new(Version::new(config.version.clone())),
+        Box::new(Age::new(config.age.clone())),
+        Box::new(Subject::new(config.subject.clone())),
+        Box::new(Signature::new(config.signature.clone())),
+        Box::new(Identity::new(config.identity.clone())),
+        Box::new(Lock::new(config.lock.clone())),
+        Box::new(Diffstat::new(config.diffstat.clone(), config.status_ttl)),
+        Box::new(Submodules::new(
+            config.submodules.clone(),
+            config.status_ttl,
+        )),
+        Box::new(Superproject::new(config.superproject.clone())),
+        Box::new(Shallow::new(config.shallow.clone())),
+        Box::new(Promisor::new(config.promisor.clone())),
+        Box::new(LfsLocks::new(config.lfs_locks.clone())),
+        Box::new(Hidden::new(config.hidden.clone())),
+        Box::new(Unpushed::new(config.unpushed.clone())),
+        Box::new(Unpublished::new(config.unpublished.clone())),
+        Box::new(Remotes::new(config.remotes.clone())),
+        Box::new(Description::new(config.description.clone())),
+        Box::new(Worktree::new(config.worktree.clone())),
+        Box::new(FetchAge::new(config.fetch_age.clone(), config.color)),
+        Box::new(Maintenance::new(config.maintenance.clone())),
+    ]
+}
+
+/// Names of every segment.
+pub fn names() -> Vec<&'static str> {
+    providers(&Config::default())
+        .iter()
+        .map(|provider| provider.name())
+        .collect()
+}
+
+fn render_cached(
+    provider: &dyn SegmentProvider,
+    ctx: &Context,
+    cache: &SegmentCache,
+    settings: &str,
+) -> Option<String> {
+    if provider.cost() == Cost::Cheap {
+        return provider.render(ctx);
+    }
+
+    let Some(key) = provider.cache_key(ctx) else {
+        return provider.render(ctx);
+    };
+    let key = format!("{settings}:{key}");
+
+    let git_dir = ctx.repo.path();
+    if let Some(output) = cache.get(git_dir, provider.name(), &key) {
+        return output;
+    }
+
+    let output = provider.render(ctx);
+    cache.insert(git_dir, provider.name(), key, output.clone());
+    output
+}
+
+/// Part of the cache key of the segments reading the working tree, whose
+/// edits don't change the git dir. It changes every `ttl` seconds, so they
+/// are recomputed as often as the status scan. `None` when `ttl` is 0, for
+/// no caching at all.
+fn worktree_epoch(ttl: i64) -> Option<i64> {
+    (ttl > 0).then(|| now() / ttl)
+}
+
+/// Joins `symbol` + `count` pairs with a space, leaving out zero counts.
+fn join_counts<N: Copy + Default + PartialEq + Display>(counts: &[(&str, N)]) -> Option<String> {
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(_, count)| *count != N::default())
+        .map(|(symbol, count)| format!("{symbol}{count}"))
+        .collect();
+
+    if parts.is_empty() {
+        return None;
+    }
+
+    Some(parts.join(" "))
+}
+
+/// Joins the text of the segments with a space.
+pub fn join(segments: &[Segment]) -> String {
+    let texts: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+    texts.join(" ")
+}
+
+/// Cuts `text` to at most `max_length` characters, ending it with `ellipsis`
+/// when it was cut.
+pub fn truncate(text: &str, max_length: usize, ellipsis: &str) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let kept = max_length.saturating_sub(ellipsis.chars().count());
+    let mut truncated: String = text.chars().take(kept).collect();
+    truncated.push_str(ellipsis);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(name: &'static str, text: &str) -> Segment {
+        Segment {
+            name,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn registry_keeps_the_order_of_the_names() {
+        let segments = ["stash", "unknown", "branch", "stash"].map(String::from);
+        let registry = Registry::new(&Config::default(), &segments);
+        let names: Vec<&str> = registry.providers.iter().map(|p| p.name()).collect();
+
+        assert_eq!(names, ["stash", "branch"]);
+    }
+
+    #[test]
+    fn names_are_unique() {
+        let names = names();
+        for (i, name) in names.iter().enumerate() {
+            assert!(!names[i + 1..].contains(name), "{name} is listed twice");
+        }
+    }
+
+    #[test]
+    fn join_counts_leaves_out_zeros() {
+        assert_eq!(
+            join_counts(&[("+", 2), ("~", 0), ("?", 5)]).as_deref(),
+            Some("+2 ?5")
+        );
+        assert_eq!(join_counts(&[("+", 0_u64), ("~", 0)]), None);
+        assert_eq!(join_counts::<i64>(&[]), None);
+    }
+
+    #[test]
+    fn join_separates_with_spaces() {
+        let segments = [segment("branch", "main"), segment("stash", "≡1")];
+        assert_eq!(join(&segments), "main ≡1");
+        assert_eq!(join(&[]), "");
+    }
+
+    #[test]
+    fn truncate_counts_characters() {
+        assert_eq!(truncate("feature", 7, "…"), "feature");
+        assert_eq!(truncate("feature", 4, "…"), "fea…");
+        assert_eq!(truncate("ünïcødé", 3, "…"), "ün…");
+        assert_eq!(truncate("feature", 5, "..."), "fe...");
+        assert_eq!(truncate("abc", 0, "…"), "…");
+    }
+
+    #[test]
+    fn worktree_epoch_is_none_without_a_ttl() {
+        assert_eq!(worktree_epoch(0), None);
+        assert_eq!(worktree_epoch(-5), None);
+        assert!(worktree_epoch(60).is_some());
+    }
+}
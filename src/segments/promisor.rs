@@ -0,0 +1,141 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct PromisorConfig {
+    pub symbol: String,
+}
+
+impl Default for PromisorConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "(partial)".to_string(),
+        }
+    }
+}
+
+impl PromisorConfig {
+    /// Keys of the `promisor` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `promisor` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("promisor", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Shown in partial clones, where missing objects are fetched on demand from
+/// a promisor remote.
+pub struct Promisor {
+    config: PromisorConfig,
+}
+
+impl Promisor {
+    pub fn new(config: PromisorConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Promisor {
+    fn name(&self) -> &'static str {
+        "promisor"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        if !is_partial_clone(ctx) {
+            return None;
+        }
+
+        Some(self.config.symbol.clone())
+    }
+}
+
+/// Whether `extensions.partialClone` is set or any remote is marked as a
+/// promisor or has a partial clone filter.
+fn is_partial_clone(ctx: &Context) -> bool {
+    let Ok(config) = ctx.repo.config() else {
+        return false;
+    };
+
+    if config.get_string("extensions.partialclone").is_ok() {
+        return true;
+    }
+
+    let Ok(mut entries) = config.entries(Some(r"^remote\..*\.(promisor|partialclonefilter)$"))
+    else {
+        return false;
+    };
+
+    while let Some(Ok(entry)) = entries.next() {
+        if entry
+            .name()
+            .is_some_and(|name| name.ends_with(".partialclonefilter"))
+        {
+            return true;
+        }
+
+        if matches!(entry.value(), Some("true" | "yes" | "on" | "1")) {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = PromisorConfig::default();
+        config.update(&record("symbol = \"◐\"")).unwrap();
+
+        assert_eq!(config.symbol, "◐");
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = PromisorConfig::default();
+        assert!(config.update(&record("symbol = [\"◐\"]")).is_err());
+    }
+
+    #[test]
+    fn renders_for_promisor_remotes() {
+        let repo = TempRepo::new();
+        let promisor = Promisor::new(PromisorConfig::default());
+        let mut config = repo.repo.config().unwrap();
+        assert_eq!(repo.render(&promisor), None);
+
+        config.set_str("remote.origin.promisor", "false").unwrap();
+        assert_eq!(repo.render(&promisor), None);
+
+        config.set_str("remote.origin.promisor", "true").unwrap();
+        assert_eq!(repo.render(&promisor).as_deref(), Some("(partial)"));
+    }
+
+    #[test]
+    fn renders_for_partial_clone_filters() {
+        let repo = TempRepo::new();
+        repo.repo
+            .config()
+            .unwrap()
+            .set_str("remote.origin.partialclonefilter", "blob:none")
+            .unwrap();
+
+        assert_eq!(
+            repo.render(&Promisor::new(PromisorConfig::default()))
+                .as_deref(),
+            Some("(partial)")
+        );
+    }
+}
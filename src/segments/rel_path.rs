@@ -0,0 +1,88 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone, Default)]
+pub struct RelPathConfig {
+    /// Text placed before the path, e.g. `repo:`.
+    pub prefix: String,
+}
+
+impl RelPathConfig {
+    /// Keys of the `rel_path` record.
+    pub const KEYS: &'static [&'static str] = &["prefix"];
+
+    /// Applies the settings of the `rel_path` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("rel_path", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "prefix", &mut self.prefix)?;
+
+        Ok(())
+    }
+}
+
+/// Current directory relative to the repository root, hidden at the root.
+pub struct RelPath {
+    config: RelPathConfig,
+}
+
+impl RelPath {
+    pub fn new(config: RelPathConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for RelPath {
+    fn name(&self) -> &'static str {
+        "rel_path"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let path = ctx.relative_path()?;
+        if path.as_os_str().is_empty() {
+            return None;
+        }
+
+        Some(format!("{}{}", self.config.prefix, path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = RelPathConfig::default();
+        config.update(&record("prefix = \"repo:\"")).unwrap();
+
+        assert_eq!(config.prefix, "repo:");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = RelPathConfig::default();
+        assert!(config.update(&record("suffix = \"/\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_path_below_the_root() {
+        let repo = TempRepo::new();
+        let rel_path = RelPath::new(RelPathConfig {
+            prefix: "repo:".to_string(),
+        });
+        assert_eq!(repo.render(&rel_path), None);
+
+        let dir = repo.path().join("src").join("bin");
+        fs::create_dir_all(&dir).unwrap();
+        let ctx = Context::new(&repo.repo, &dir);
+        assert_eq!(rel_path.render(&ctx).as_deref(), Some("repo:src/bin"));
+    }
+}
@@ -0,0 +1,322 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_pairs, set_string, set_usize};
+use crate::context::Context;
+use crate::segments::{truncate, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct RemoteConfig {
+    /// Symbol used when the remote URL matches none of `icons`.
+    pub symbol: String,
+    /// Pairs of URL substring and the symbol shown when the upstream's
+    /// remote URL contains it, checked in order.
+    pub icons: Vec<(String, String)>,
+    /// Shown when the configured upstream branch no longer exists.
+    pub gone: String,
+    /// Shown when the current branch has no upstream configured at all,
+    /// nothing when it's empty.
+    pub local: String,
+    /// Show the upstream branch name next to the symbol.
+    pub show_upstream: bool,
+    /// Display `main` instead of `origin/main` when the upstream is on `origin`.
+    pub strip_origin: bool,
+    /// Show the name of the upstream's remote when it isn't `origin`.
+    pub show_remote_name: bool,
+    /// Show the upstream name, even without `show_upstream`, when its branch
+    /// name differs from the local one.
+    pub show_mismatched_upstream: bool,
+    /// Longest upstream name shown before it is cut with `ellipsis`.
+    pub max_upstream_length: usize,
+    /// Ends a cut upstream name.
+    pub ellipsis: String,
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "\u{f1d3}".to_string(),
+            icons: vec![
+                ("github".to_string(), "\u{f09b}".to_string()),
+                ("gitlab".to_string(), "\u{f296}".to_string()),
+                ("bitbucket".to_string(), "\u{f171}".to_string()),
+                ("codeberg".to_string(), "\u{f330}".to_string()),
+            ],
+            show_upstream: false,
+            strip_origin: false,
+            show_remote_name: true,
+            show_mismatched_upstream: true,
+            max_upstream_length: 24,
+            ellipsis: "…".to_string(),
+            gone: "gone".to_string(),
+            local: "↟?".to_string(),
+        }
+    }
+}
+
+impl RemoteConfig {
+    /// Keys of the `remote` record.
+    pub const KEYS: &'static [&'static str] = &[
+        "symbol",
+        "icons",
+        "gone",
+        "local",
+        "show_upstream",
+        "strip_origin",
+        "show_remote_name",
+        "show_mismatched_upstream",
+        "max_upstream_length",
+        "ellipsis",
+    ];
+
+    /// Applies the settings of the `remote` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("remote", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+        set_pairs(record, "icons", &mut self.icons)?;
+        set_string(record, "gone", &mut self.gone)?;
+        set_string(record, "local", &mut self.local)?;
+        set_bool(record, "show_upstream", &mut self.show_upstream)?;
+        set_bool(record, "strip_origin", &mut self.strip_origin)?;
+        set_bool(record, "show_remote_name", &mut self.show_remote_name)?;
+        set_bool(
+            record,
+            "show_mismatched_upstream",
+            &mut self.show_mismatched_upstream,
+        )?;
+        set_usize(record, "max_upstream_length", &mut self.max_upstream_length)?;
+        set_string(record, "ellipsis", &mut self.ellipsis)?;
+
+        Ok(())
+    }
+}
+
+/// Shows an icon for the hosting provider of the upstream, and optionally the
+/// upstream name, when the current branch has an upstream. Branches without
+/// one get the `local` marker instead.
+pub struct Remote {
+    config: RemoteConfig,
+}
+
+impl Remote {
+    pub fn new(config: RemoteConfig) -> Self {
+        Self { config }
+    }
+
+    /// Hosting provider icon of the upstream's remote. Remotes not named
+    /// `origin` are shown by name, after the icon when there is one.
+    fn symbol(&self, ctx: &Context) -> String {
+        let remote_name = &ctx.head().remote_name;
+        let icon = self.icon(ctx);
+
+        if !self.config.show_remote_name || remote_name.is_empty() || remote_name == "origin" {
+            return icon.unwrap_or(&self.config.symbol).to_string();
+        }
+
+        match icon {
+            Some(icon) => format!("{icon} {remote_name}"),
+            None => remote_name.clone(),
+        }
+    }
+
+    fn icon(&self, ctx: &Context) -> Option<&str> {
+        let url = ctx
+            .repo
+            .find_remote(&ctx.head().remote_name)
+            .ok()
+            .and_then(|remote| remote.url().map(str::to_lowercase))?;
+
+        self.config
+            .icons
+            .iter()
+            .find(|(pattern, _)| url.contains(&pattern.to_lowercase()))
+            .map(|(_, icon)| icon.as_str())
+    }
+}
+
+impl SegmentProvider for Remote {
+    fn name(&self) -> &'static str {
+        "remote"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let head = ctx.head();
+        if head.upstream_gone {
+            return Some(self.config.gone.clone());
+        }
+
+        let upstream = &head.remote;
+        if upstream.is_empty() {
+            // Detached and unborn HEADs have no local branch to push.
+            if head.local.is_none() || self.config.local.is_empty() {
+                return None;
+            }
+
+            return Some(self.config.local.clone());
+        }
+
+        let symbol = self.symbol(ctx);
+        let mismatched = self.config.show_mismatched_upstream && {
+            let prefix = format!("{}/", head.remote_name);
+            upstream.strip_prefix(&prefix).unwrap_or(upstream) != head.branch
+        };
+
+        if !self.config.show_upstream && !mismatched {
+            return Some(symbol);
+        }
+
+        let upstream = match upstream.strip_prefix("origin/") {
+            Some(branch) if self.config.strip_origin => branch,
+            _ => upstream,
+        };
+
+        let upstream = truncate(
+            upstream,
+            self.config.max_upstream_length,
+            &self.config.ellipsis,
+        );
+
+        Some(format!("{symbol} {upstream}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = RemoteConfig::default();
+        config
+            .update(&record(
+                "local = \"local\"\nshow_upstream = true\n[icons]\n\"git.example.com\" = \"E\"",
+            ))
+            .unwrap();
+
+        assert_eq!(config.local, "local");
+        assert!(config.show_upstream);
+        assert_eq!(
+            config.icons,
+            vec![("git.example.com".to_string(), "E".to_string())]
+        );
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = RemoteConfig::default();
+        assert!(config.update(&record("icons = [\"github\"]")).is_err());
+        assert!(config
+            .update(&record("max_upstream_length = true"))
+            .is_err());
+    }
+
+    #[test]
+    fn renders_the_icon_of_the_upstream_host() {
+        let repo = TempRepo::new();
+        let head = repo.commit_file("a", "a");
+        let remote = Remote::new(RemoteConfig::default());
+        assert_eq!(repo.render(&remote).as_deref(), Some("↟?"));
+
+        repo.set_upstream("https://git.example.com/repo.git", head);
+        assert_eq!(repo.render(&remote).as_deref(), Some("\u{f1d3}"));
+
+        repo.repo
+            .remote_set_url("origin", "git@GitHub.com:user/repo.git")
+            .unwrap();
+        assert_eq!(repo.render(&remote).as_deref(), Some("\u{f09b}"));
+    }
+
+    #[test]
+    fn renders_the_upstream_name() {
+        let repo = TempRepo::new();
+        let head = repo.commit_file("a", "a");
+        repo.set_upstream("https://git.example.com/repo.git", head);
+
+        let config = RemoteConfig {
+            show_upstream: true,
+            ..RemoteConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Remote::new(config.clone())).as_deref(),
+            Some("\u{f1d3} origin/main")
+        );
+
+        let config = RemoteConfig {
+            strip_origin: true,
+            max_upstream_length: 3,
+            ..config
+        };
+        assert_eq!(
+            repo.render(&Remote::new(config)).as_deref(),
+            Some("\u{f1d3} ma…")
+        );
+    }
+
+    #[test]
+    fn renders_a_mismatched_upstream() {
+        let repo = TempRepo::new();
+        let head = repo.commit_file("a", "a");
+        repo.repo
+            .remote("fork", "https://git.example.com/fork.git")
+            .unwrap();
+        repo.repo
+            .reference("refs/remotes/fork/feature", head, true, "test")
+            .unwrap();
+        let mut config = repo.repo.config().unwrap();
+        config.set_str("branch.main.remote", "fork").unwrap();
+        config
+            .set_str("branch.main.merge", "refs/heads/feature")
+            .unwrap();
+
+        assert_eq!(
+            repo.render(&Remote::new(RemoteConfig::default()))
+                .as_deref(),
+            Some("fork fork/feature")
+        );
+
+        let config = RemoteConfig {
+            show_remote_name: false,
+            ..RemoteConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Remote::new(config.clone())).as_deref(),
+            Some("\u{f1d3} fork/feature")
+        );
+
+        let config = RemoteConfig {
+            show_mismatched_upstream: false,
+            ..config
+        };
+        assert_eq!(
+            repo.render(&Remote::new(config)).as_deref(),
+            Some("\u{f1d3}")
+        );
+    }
+
+    #[test]
+    fn renders_local_and_gone_branches() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let remote = Remote::new(RemoteConfig::default());
+        assert_eq!(repo.render(&remote).as_deref(), Some("↟?"));
+
+        let config = RemoteConfig {
+            local: String::new(),
+            ..RemoteConfig::default()
+        };
+        assert_eq!(repo.render(&Remote::new(config)), None);
+
+        // The upstream is configured but its remote-tracking ref is missing.
+        repo.repo
+            .remote("origin", "https://git.example.com/repo.git")
+            .unwrap();
+        let mut git_config = repo.repo.config().unwrap();
+        git_config.set_str("branch.main.remote", "origin").unwrap();
+        git_config
+            .set_str("branch.main.merge", "refs/heads/main")
+            .unwrap();
+        assert_eq!(repo.render(&remote).as_deref(), Some("gone"));
+    }
+}
@@ -0,0 +1,113 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct RemotesConfig {
+    pub symbol: String,
+    /// List the remote names instead of counting them.
+    pub list: bool,
+}
+
+impl Default for RemotesConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "⇄".to_string(),
+            list: false,
+        }
+    }
+}
+
+impl RemotesConfig {
+    /// Keys of the `remotes` record.
+    pub const KEYS: &'static [&'static str] = &["symbol", "list"];
+
+    /// Applies the settings of the `remotes` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("remotes", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+        set_bool(record, "list", &mut self.list)?;
+
+        Ok(())
+    }
+}
+
+/// Number of remotes, or their names, when there is more than one.
+pub struct Remotes {
+    config: RemotesConfig,
+}
+
+impl Remotes {
+    pub fn new(config: RemotesConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Remotes {
+    fn name(&self) -> &'static str {
+        "remotes"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let remotes = ctx.repo.remotes().ok()?;
+        if remotes.len() < 2 {
+            return None;
+        }
+
+        if self.config.list {
+            let names: Vec<&str> = remotes.iter().flatten().collect();
+            return Some(format!("{}{}", self.config.symbol, names.join(",")));
+        }
+
+        Some(format!("{}{}", self.config.symbol, remotes.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = RemotesConfig::default();
+        config.update(&record("list = true")).unwrap();
+
+        assert_eq!(config.symbol, "⇄");
+        assert!(config.list);
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = RemotesConfig::default();
+        assert!(config.update(&record("list = \"yes\"")).is_err());
+    }
+
+    #[test]
+    fn renders_more_than_one_remote() {
+        let repo = TempRepo::new();
+        let remotes = Remotes::new(RemotesConfig::default());
+        repo.repo
+            .remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        assert_eq!(repo.render(&remotes), None);
+
+        repo.repo
+            .remote("fork", "https://example.com/fork.git")
+            .unwrap();
+        assert_eq!(repo.render(&remotes).as_deref(), Some("⇄2"));
+
+        let config = RemotesConfig {
+            list: true,
+            ..RemotesConfig::default()
+        };
+        assert_eq!(
+            repo.render(&Remotes::new(config)).as_deref(),
+            Some("⇄fork,origin")
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct ShallowConfig {
+    pub symbol: String,
+}
+
+impl Default for ShallowConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "(shallow)".to_string(),
+        }
+    }
+}
+
+impl ShallowConfig {
+    /// Keys of the `shallow` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `shallow` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("shallow", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Shown in shallow clones, where history is cut off.
+pub struct Shallow {
+    config: ShallowConfig,
+}
+
+impl Shallow {
+    pub fn new(config: ShallowConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Shallow {
+    fn name(&self) -> &'static str {
+        "shallow"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        if !ctx.repo.is_shallow() {
+            return None;
+        }
+
+        Some(self.config.symbol.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = ShallowConfig::default();
+        config.update(&record("symbol = \"≈\"")).unwrap();
+
+        assert_eq!(config.symbol, "≈");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = ShallowConfig::default();
+        assert!(config.update(&record("depth = 1")).is_err());
+    }
+
+    #[test]
+    fn renders_in_shallow_clones() {
+        let repo = TempRepo::new();
+        let head = repo.commit_file("a", "a");
+        let shallow = Shallow::new(ShallowConfig::default());
+        assert_eq!(repo.render(&shallow), None);
+
+        fs::write(repo.repo.path().join("shallow"), format!("{head}\n")).unwrap();
+        assert_eq!(repo.render(&shallow).as_deref(), Some("(shallow)"));
+    }
+}
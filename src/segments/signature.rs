@@ -0,0 +1,255 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_string};
+use crate::context::Context;
+use crate::segments::{Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct SignatureConfig {
+    pub signed: String,
+    pub unsigned: String,
+    /// Shown for GPG signatures that `gpg --verify` accepts.
+    pub verified: String,
+    /// Shown for GPG signatures that `gpg --verify` rejects.
+    pub invalid: String,
+    /// Check GPG signatures with `gpg`, when it is installed.
+    pub verify: bool,
+}
+
+impl Default for SignatureConfig {
+    fn default() -> Self {
+        Self {
+            signed: "\u{f023}".to_string(),
+            unsigned: "\u{f09c}".to_string(),
+            verified: "\u{f00c}".to_string(),
+            invalid: "\u{f00d}".to_string(),
+            verify: false,
+        }
+    }
+}
+
+impl SignatureConfig {
+    /// Keys of the `signature` record.
+    pub const KEYS: &'static [&'static str] =
+        &["signed", "unsigned", "verified", "invalid", "verify"];
+
+    /// Applies the settings of the `signature` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("signature", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "signed", &mut self.signed)?;
+        set_string(record, "unsigned", &mut self.unsigned)?;
+        set_string(record, "verified", &mut self.verified)?;
+        set_string(record, "invalid", &mut self.invalid)?;
+        set_bool(record, "verify", &mut self.verify)?;
+
+        Ok(())
+    }
+}
+
+/// Whether the HEAD commit is GPG or SSH signed.
+pub struct Signature {
+    config: SignatureConfig,
+}
+
+impl Signature {
+    pub fn new(config: SignatureConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Signature {
+    fn name(&self) -> &'static str {
+        "signature"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let head = ctx.repo.head().ok()?.target()?;
+        Some(head.to_string())
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let head = ctx.repo.head().ok()?.peel_to_commit().ok()?;
+
+        let Ok((signature, signed_data)) = ctx.repo.extract_signature(&head.id(), None) else {
+            return Some(self.config.unsigned.clone());
+        };
+
+        let signature = signature.as_str().unwrap_or_default();
+        if !self.config.verify || !signature.starts_with("-----BEGIN PGP SIGNATURE") {
+            return Some(self.config.signed.clone());
+        }
+
+        match gpg_verify(signature, &signed_data) {
+            Some(true) => Some(self.config.verified.clone()),
+            Some(false) => Some(self.config.invalid.clone()),
+            None => Some(self.config.signed.clone()),
+        }
+    }
+}
+
+/// How long gpg gets, e.g. while it looks up a key, before the signature is
+/// shown as merely signed.
+const GPG_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Runs `gpg --verify`, `None` when gpg couldn't be run at all or timed out.
+fn gpg_verify(signature: &str, signed_data: &[u8]) -> Option<bool> {
+    let (signature_path, mut file) = create_signature_file()?;
+    let result = file
+        .write_all(signature.as_bytes())
+        .ok()
+        .and_then(|()| run_gpg(&signature_path, signed_data));
+
+    let _ = fs::remove_file(&signature_path);
+    result
+}
+
+/// Creates a new file only the current user can read, never reusing an
+/// existing file or following a symlink planted at the same path.
+fn create_signature_file() -> Option<(PathBuf, File)> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    for _ in 0..16 {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.subsec_nanos());
+        let path = std::env::temp_dir().join(format!(
+            "nu_plugin_git_prompt-{}-{}-{nanos}.sig",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        match options.open(&path) {
+            Ok(file) => return Some((path, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+fn run_gpg(signature_path: &Path, signed_data: &[u8]) -> Option<bool> {
+    let mut child = Command::new("gpg")
+        .args(["--batch", "--no-tty", "--verify"])
+        .arg(signature_path)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Dropping stdin once written closes it, so gpg sees the end of the data.
+    let written = child
+        .stdin
+        .take()
+        .and_then(|mut stdin| stdin.write_all(signed_data).ok());
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return written.map(|()| status.success()),
+            Ok(None) if started.elapsed() < GPG_TIMEOUT => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    /// Commits the index with `signature` in its `gpgsig` header and moves
+    /// `main` to it.
+    fn commit_signed(repo: &TempRepo, signature: &str) {
+        let git = &repo.repo;
+        let tree = git
+            .find_tree(git.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let author = git2::Signature::now("Test", "test@example.com").unwrap();
+        let buffer = git
+            .commit_create_buffer(&author, &author, "Signed", &tree, &[])
+            .unwrap();
+        let oid = git
+            .commit_signed(buffer.as_str().unwrap(), signature, None)
+            .unwrap();
+        git.reference("refs/heads/main", oid, true, "test").unwrap();
+    }
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = SignatureConfig::default();
+        config
+            .update(&record("signed = \"S\"\nverify = true"))
+            .unwrap();
+
+        assert_eq!(config.signed, "S");
+        assert!(config.verify);
+        assert_eq!(config.unsigned, "\u{f09c}");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = SignatureConfig::default();
+        assert!(config.update(&record("gpg = true")).is_err());
+    }
+
+    #[test]
+    fn renders_whether_head_is_signed() {
+        let repo = TempRepo::new();
+        let signature = Signature::new(SignatureConfig::default());
+        assert_eq!(repo.render(&signature), None);
+
+        repo.commit_file("a", "a");
+        assert_eq!(repo.render(&signature).as_deref(), Some("\u{f09c}"));
+
+        commit_signed(
+            &repo,
+            "-----BEGIN SSH SIGNATURE-----\nU1NIU0lH\n-----END SSH SIGNATURE-----",
+        );
+        assert_eq!(repo.render(&signature).as_deref(), Some("\u{f023}"));
+    }
+
+    #[test]
+    fn signature_files_are_new_and_private() {
+        let (first, _) = create_signature_file().unwrap();
+        let (second, _) = create_signature_file().unwrap();
+        assert_ne!(first, second);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+}
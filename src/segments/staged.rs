@@ -0,0 +1,115 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{join_counts, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct StagedConfig {
+    pub new: String,
+    pub modified: String,
+    pub renamed: String,
+    pub typechange: String,
+}
+
+impl Default for StagedConfig {
+    fn default() -> Self {
+        Self {
+            new: "+".to_string(),
+            modified: "+~".to_string(),
+            renamed: "+->".to_string(),
+            typechange: "+t".to_string(),
+        }
+    }
+}
+
+impl StagedConfig {
+    /// Keys of the `staged` record.
+    pub const KEYS: &'static [&'static str] = &["new", "modified", "renamed", "typechange"];
+
+    /// Applies the settings of the `staged` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("staged", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "new", &mut self.new)?;
+        set_string(record, "modified", &mut self.modified)?;
+        set_string(record, "renamed", &mut self.renamed)?;
+        set_string(record, "typechange", &mut self.typechange)?;
+
+        Ok(())
+    }
+}
+
+/// Counts of changes added to the index.
+pub struct Staged {
+    config: StagedConfig,
+}
+
+impl Staged {
+    pub fn new(config: StagedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Staged {
+    fn name(&self) -> &'static str {
+        "staged"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let status = ctx.status()?;
+
+        join_counts(&[
+            (&self.config.new, status.index_new),
+            (&self.config.modified, status.index_modified),
+            (&self.config.renamed, status.index_renamed),
+            (&self.config.typechange, status.index_typechange),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = StagedConfig::default();
+        config
+            .update(&record("new = \"A\"\nrenamed = \"R\""))
+            .unwrap();
+
+        assert_eq!(config.new, "A");
+        assert_eq!(config.modified, "+~");
+        assert_eq!(config.renamed, "R");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = StagedConfig::default();
+        assert!(config.update(&record("deleted = \"D\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_staged_counts() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a\n");
+        repo.commit_file("b", "some longer contents\nto detect the rename\n");
+        let staged = Staged::new(StagedConfig::default());
+        assert_eq!(repo.render(&staged), None);
+
+        repo.write("a", "changed\n");
+        repo.stage("a");
+        repo.write("c", "c\n");
+        repo.stage("c");
+        repo.write("d", "d\n");
+        repo.stage("d");
+        repo.remove("b");
+        repo.unstage("b");
+        repo.write("e", "some longer contents\nto detect the rename\n");
+        repo.stage("e");
+        assert_eq!(repo.render(&staged).as_deref(), Some("+2 +~1 +->1"));
+    }
+}
@@ -0,0 +1,127 @@
+use git2::Repository;
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_string};
+use crate::context::Context;
+use crate::segments::{Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct StashConfig {
+    pub enabled: bool,
+    pub symbol: String,
+}
+
+impl Default for StashConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            symbol: "≡".to_string(),
+        }
+    }
+}
+
+impl StashConfig {
+    /// Keys of the `stash` record.
+    pub const KEYS: &'static [&'static str] = &["enabled", "symbol"];
+
+    /// Applies the settings of the `stash` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("stash", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_bool(record, "enabled", &mut self.enabled)?;
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Number of stashes.
+pub struct Stash {
+    config: StashConfig,
+}
+
+impl Stash {
+    pub fn new(config: StashConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Stash {
+    fn name(&self) -> &'static str {
+        "stash"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        Some(ctx.git_file_stamp("logs/refs/stash"))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let count = stash_count(ctx.repo);
+        if count == 0 {
+            return None;
+        }
+
+        Some(format!("{}{count}", self.config.symbol))
+    }
+}
+
+/// Counts the stashes of `repo`, which are the entries of the reflog of
+/// `refs/stash`, so no mutable handle is needed as with `stash_foreach`.
+pub fn stash_count(repo: &Repository) -> usize {
+    repo.reflog("refs/stash").map_or(0, |reflog| reflog.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = StashConfig::default();
+        config
+            .update(&record("enabled = false\nsymbol = \"$\""))
+            .unwrap();
+
+        assert!(!config.enabled);
+        assert_eq!(config.symbol, "$");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = StashConfig::default();
+        assert!(config.update(&record("count = true")).is_err());
+    }
+
+    #[test]
+    fn renders_the_stash_count() {
+        let mut repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let stash = Stash::new(StashConfig::default());
+        assert_eq!(repo.render(&stash), None);
+
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        for contents in ["b", "c"] {
+            repo.write("a", contents);
+            repo.repo.stash_save(&signature, "WIP", None).unwrap();
+        }
+        assert_eq!(stash_count(&repo.repo), 2);
+        assert_eq!(repo.render(&stash).as_deref(), Some("≡2"));
+
+        let config = StashConfig {
+            enabled: false,
+            ..StashConfig::default()
+        };
+        assert_eq!(repo.render(&Stash::new(config)), None);
+    }
+}
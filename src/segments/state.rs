@@ -0,0 +1,264 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_bool, set_string, set_style};
+use crate::context::Context;
+use crate::format::parse_style;
+use crate::operation::OperationKind;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct StateConfig {
+    pub merge: String,
+    pub rebase: String,
+    /// Label of a rebase whose progress is known, followed by `step/total`.
+    pub rebase_progress: String,
+    pub cherry_pick: String,
+    pub revert: String,
+    pub bisect: String,
+    pub am: String,
+    /// Show what is being merged, cherry-picked or reverted.
+    pub show_source: bool,
+    /// Show the subject of the commit being cherry-picked or reverted.
+    pub show_subject: bool,
+    /// Shown before the number of todo entries left in an interactive rebase.
+    pub todo_symbol: String,
+    /// Starship-style style applied to the segment, e.g. `bold yellow`. The
+    /// text is left unstyled when it's empty.
+    pub style: String,
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            merge: "MERGING".to_string(),
+            rebase: "REBASING".to_string(),
+            rebase_progress: "REBASE".to_string(),
+            cherry_pick: "CHERRY-PICKING".to_string(),
+            revert: "REVERTING".to_string(),
+            bisect: "BISECTING".to_string(),
+            am: "AM".to_string(),
+            show_source: true,
+            show_subject: false,
+            todo_symbol: "⋯".to_string(),
+            style: "bold yellow".to_string(),
+        }
+    }
+}
+
+impl StateConfig {
+    /// Keys of the `state` record.
+    pub const KEYS: &'static [&'static str] = &[
+        "merge",
+        "rebase",
+        "rebase_progress",
+        "cherry_pick",
+        "revert",
+        "bisect",
+        "am",
+        "show_source",
+        "show_subject",
+        "todo_symbol",
+        "style",
+    ];
+
+    /// Applies the settings of the `state` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("state", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "merge", &mut self.merge)?;
+        set_string(record, "rebase", &mut self.rebase)?;
+        set_string(record, "rebase_progress", &mut self.rebase_progress)?;
+        set_string(record, "cherry_pick", &mut self.cherry_pick)?;
+        set_string(record, "revert", &mut self.revert)?;
+        set_string(record, "bisect", &mut self.bisect)?;
+        set_string(record, "am", &mut self.am)?;
+        set_bool(record, "show_source", &mut self.show_source)?;
+        set_bool(record, "show_subject", &mut self.show_subject)?;
+        set_string(record, "todo_symbol", &mut self.todo_symbol)?;
+        set_style(record, "style", &mut self.style)?;
+
+        Ok(())
+    }
+}
+
+/// Operation in progress, such as a merge or a rebase.
+pub struct State {
+    config: StateConfig,
+    color: bool,
+}
+
+impl State {
+    pub fn new(config: StateConfig, color: bool) -> Self {
+        Self { config, color }
+    }
+}
+
+impl SegmentProvider for State {
+    fn name(&self) -> &'static str {
+        "state"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let operation = ctx.operation()?;
+
+        let progress = operation.step.zip(operation.total);
+        let label = match operation.kind {
+            OperationKind::Merge => &self.config.merge,
+            OperationKind::Rebase if progress.is_some() => &self.config.rebase_progress,
+            OperationKind::Rebase => &self.config.rebase,
+            OperationKind::CherryPick => &self.config.cherry_pick,
+            OperationKind::Revert => &self.config.revert,
+            OperationKind::Bisect => &self.config.bisect,
+            OperationKind::ApplyMailbox => &self.config.am,
+        };
+
+        let mut text = label.clone();
+        if let Some(source) = operation
+            .source
+            .as_ref()
+            .filter(|_| self.config.show_source)
+        {
+            text.push(' ');
+            text.push_str(source);
+        }
+
+        if let Some(subject) = operation
+            .subject
+            .as_ref()
+            .filter(|_| self.config.show_subject)
+        {
+            text.push_str(&format!(" \"{subject}\""));
+        }
+
+        if let Some((step, total)) = progress {
+            text.push_str(&format!(" {step}/{total}"));
+        }
+
+        if let Some(steps) = operation.bisect_steps() {
+            text.push_str(&format!(" ~{steps} steps"));
+        }
+
+        if let Some(todo) = operation.todo.filter(|todo| *todo > 0) {
+            text.push_str(&format!(" {}{todo}", self.config.todo_symbol));
+        }
+
+        if !self.color {
+            return Some(text);
+        }
+
+        Some(parse_style(&self.config.style).paint(text).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = StateConfig::default();
+        config
+            .update(&record(
+                "merge = \"M\"\nshow_source = false\nstyle = \"bold yellow\"",
+            ))
+            .unwrap();
+
+        assert_eq!(config.merge, "M");
+        assert!(!config.show_source);
+        assert_eq!(config.style, "bold yellow");
+    }
+
+    #[test]
+    fn update_rejects_invalid_styles() {
+        let mut config = StateConfig::default();
+        assert!(config.update(&record("style = \"shiny\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_operation_and_its_source() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let state = State::new(StateConfig::default(), true);
+        assert_eq!(repo.render(&state), None);
+
+        // Highlighted unless colors are off.
+        repo.merge_conflict(&["a"], &[]);
+        assert_eq!(
+            repo.render(&state),
+            Some(
+                parse_style("bold yellow")
+                    .paint("MERGING theirs")
+                    .to_string()
+            )
+        );
+
+        let config = StateConfig {
+            show_source: false,
+            ..StateConfig::default()
+        };
+        assert_eq!(
+            repo.render(&State::new(config.clone(), false)).as_deref(),
+            Some("MERGING")
+        );
+
+        let config = StateConfig {
+            style: String::new(),
+            ..config
+        };
+        assert_eq!(
+            repo.render(&State::new(config, true)).as_deref(),
+            Some("MERGING")
+        );
+    }
+
+    #[test]
+    fn renders_the_rebase_progress() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let dir = repo.repo.path().join("rebase-merge");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("interactive"), "").unwrap();
+        fs::write(dir.join("msgnum"), "2").unwrap();
+        fs::write(dir.join("end"), "5").unwrap();
+        fs::write(dir.join("git-rebase-todo"), "pick 1234567 One\n").unwrap();
+
+        assert_eq!(
+            repo.render(&State::new(StateConfig::default(), false))
+                .as_deref(),
+            Some("REBASE 2/5 ⋯1")
+        );
+
+        fs::remove_file(dir.join("msgnum")).unwrap();
+        assert_eq!(
+            repo.render(&State::new(StateConfig::default(), false))
+                .as_deref(),
+            Some("REBASING ⋯1")
+        );
+    }
+
+    #[test]
+    fn renders_the_subject_of_a_cherry_pick() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let picked = repo.commit_file("b", "b");
+        fs::write(
+            repo.repo.path().join("CHERRY_PICK_HEAD"),
+            picked.to_string(),
+        )
+        .unwrap();
+
+        let config = StateConfig {
+            show_source: false,
+            show_subject: true,
+            ..StateConfig::default()
+        };
+        assert_eq!(
+            repo.render(&State::new(config, false)).as_deref(),
+            Some("CHERRY-PICKING \"Change b\"")
+        );
+    }
+}
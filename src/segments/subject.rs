@@ -0,0 +1,104 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string, set_usize};
+use crate::context::Context;
+use crate::segments::{truncate, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct SubjectConfig {
+    /// Longest subject shown, in characters, before it is cut with `ellipsis`.
+    pub max_length: usize,
+    /// Ends a cut subject.
+    pub ellipsis: String,
+}
+
+impl Default for SubjectConfig {
+    fn default() -> Self {
+        Self {
+            max_length: 30,
+            ellipsis: "…".to_string(),
+        }
+    }
+}
+
+impl SubjectConfig {
+    /// Keys of the `subject` record.
+    pub const KEYS: &'static [&'static str] = &["max_length", "ellipsis"];
+
+    /// Applies the settings of the `subject` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("subject", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_usize(record, "max_length", &mut self.max_length)?;
+        set_string(record, "ellipsis", &mut self.ellipsis)?;
+
+        Ok(())
+    }
+}
+
+/// Subject line of the HEAD commit.
+pub struct Subject {
+    config: SubjectConfig,
+}
+
+impl Subject {
+    pub fn new(config: SubjectConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Subject {
+    fn name(&self) -> &'static str {
+        "subject"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let commit = ctx.repo.head().ok()?.peel_to_commit().ok()?;
+        let subject = commit.summary()?;
+
+        Some(truncate(
+            subject,
+            self.config.max_length,
+            &self.config.ellipsis,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = SubjectConfig::default();
+        config.update(&record("max_length = 72")).unwrap();
+
+        assert_eq!(config.max_length, 72);
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = SubjectConfig::default();
+        assert!(config.update(&record("length = 72")).is_err());
+    }
+
+    #[test]
+    fn renders_the_subject_of_head() {
+        let repo = TempRepo::new();
+        let subject = Subject::new(SubjectConfig::default());
+        assert_eq!(repo.render(&subject), None);
+
+        repo.write("a", "a");
+        repo.stage("a");
+        repo.commit("Add the parser\n\nWith a body.");
+        assert_eq!(repo.render(&subject).as_deref(), Some("Add the parser"));
+
+        let config = SubjectConfig {
+            max_length: 5,
+            ..SubjectConfig::default()
+        };
+        assert_eq!(repo.render(&Subject::new(config)).as_deref(), Some("Add …"));
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt::Write;
+
+use git2::{SubmoduleIgnore, SubmoduleStatus};
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::{file_stamp, Context};
+use crate::repo;
+use crate::segments::{join_counts, worktree_epoch, Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct SubmodulesConfig {
+    /// Shown before the number of submodules with uncommitted changes.
+    pub dirty: String,
+    /// Shown before the number of submodules whose checked-out commit differs
+    /// from the one recorded in the superproject, or that aren't initialized.
+    pub outdated: String,
+}
+
+impl Default for SubmodulesConfig {
+    fn default() -> Self {
+        Self {
+            dirty: "sub~".to_string(),
+            outdated: "sub↻".to_string(),
+        }
+    }
+}
+
+impl SubmodulesConfig {
+    /// Keys of the `submodules` record.
+    pub const KEYS: &'static [&'static str] = &["dirty", "outdated"];
+
+    /// Applies the settings of the `submodules` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("submodules", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "dirty", &mut self.dirty)?;
+        set_string(record, "outdated", &mut self.outdated)?;
+
+        Ok(())
+    }
+}
+
+/// Counts of submodules needing attention.
+pub struct Submodules {
+    config: SubmodulesConfig,
+    status_ttl: i64,
+}
+
+impl Submodules {
+    pub fn new(config: SubmodulesConfig, status_ttl: i64) -> Self {
+        Self { config, status_ttl }
+    }
+
+    /// The index of the superproject, `.gitmodules`, and the HEAD and index
+    /// of every submodule.
+    fn key(&self, ctx: &Context, epoch: i64) -> Option<String> {
+        let workdir = ctx.repo.workdir()?;
+        let submodules = ctx.repo.submodules().ok()?;
+
+        let mut key = format!(
+            "{epoch}:{}:{}",
+            ctx.git_file_stamp("index"),
+            file_stamp(&workdir.join(".gitmodules"))
+        );
+        for submodule in &submodules {
+            // Reading the index and the HEAD of a submodule opens it.
+            let index = repo::checked(|| submodule.open())
+                .map(|repo| file_stamp(&repo.path().join("index")))
+                .unwrap_or_default();
+            let head = repo::checked(|| submodule.workdir_id());
+            write!(key, ":{}={head:?}/{index}", submodule.path().display()).ok()?;
+        }
+
+        Some(key)
+    }
+
+    fn counts(&self, ctx: &Context) -> Option<String> {
+        let submodules = ctx.repo.submodules().ok()?;
+
+        let dirty_flags = SubmoduleStatus::WD_INDEX_MODIFIED
+            | SubmoduleStatus::WD_WD_MODIFIED
+            | SubmoduleStatus::WD_UNTRACKED;
+
+        let outdated_flags = SubmoduleStatus::WD_MODIFIED | SubmoduleStatus::WD_UNINITIALIZED;
+
+        let mut dirty: u16 = 0;
+        let mut outdated: u16 = 0;
+        for submodule in &submodules {
+            let Some(name) = submodule.name() else {
+                continue;
+            };
+
+            // The status opens the submodule, so it takes the owner check
+            // like any other open.
+            let Ok(status) =
+                repo::checked(|| ctx.repo.submodule_status(name, SubmoduleIgnore::None))
+            else {
+                continue;
+            };
+
+            if status.intersects(dirty_flags) {
+                dirty += 1;
+            }
+
+            if status.intersects(outdated_flags) {
+                outdated += 1;
+            }
+        }
+
+        join_counts(&[
+            (&self.config.dirty, dirty),
+            (&self.config.outdated, outdated),
+        ])
+    }
+}
+
+impl SegmentProvider for Submodules {
+    fn name(&self) -> &'static str {
+        "submodules"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let epoch = worktree_epoch(self.status_ttl)?;
+        self.key(ctx, epoch)
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        self.counts(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = SubmodulesConfig::default();
+        config.update(&record("dirty = \"S*\"")).unwrap();
+
+        assert_eq!(config.dirty, "S*");
+        assert_eq!(config.outdated, "sub↻");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = SubmodulesConfig::default();
+        assert!(config.update(&record("uninitialized = \"?\"")).is_err());
+    }
+
+    #[test]
+    fn renders_dirty_and_outdated_submodules() {
+        let source = TempRepo::new();
+        source.commit_file("lib", "lib\n");
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let child = repo.add_submodule(&source, "one");
+        repo.add_submodule(&source, "two");
+        let submodules = Submodules::new(SubmodulesConfig::default(), 0);
+        assert_eq!(repo.render(&submodules), None);
+
+        fs::write(repo.path().join("one/lib"), "changed\n").unwrap();
+        assert_eq!(repo.render(&submodules).as_deref(), Some("sub~1"));
+
+        let mut index = child.index().unwrap();
+        index.add_path(std::path::Path::new("lib")).unwrap();
+        index.write().unwrap();
+        let tree = child.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = child.head().unwrap().peel_to_commit().unwrap();
+        child
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Change",
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+        assert_eq!(repo.render(&submodules).as_deref(), Some("sub↻1"));
+    }
+
+    #[test]
+    fn cache_key_follows_submodule_commits() {
+        let source = TempRepo::new();
+        source.commit_file("lib", "lib\n");
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let child = repo.add_submodule(&source, "one");
+        let submodules = Submodules::new(SubmodulesConfig::default(), 3_600);
+        let key = || submodules.cache_key(&Context::new(&repo.repo, repo.path()));
+
+        let before = key().unwrap();
+        let head = child.head().unwrap().peel_to_commit().unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        child
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Empty",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+        assert_ne!(key().unwrap(), before);
+        assert_eq!(
+            Submodules::new(SubmodulesConfig::default(), 0)
+                .cache_key(&Context::new(&repo.repo, repo.path())),
+            None
+        );
+    }
+}
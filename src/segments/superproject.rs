@@ -0,0 +1,126 @@
+use std::path::Component;
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::repo;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct SuperprojectConfig {
+    pub symbol: String,
+}
+
+impl Default for SuperprojectConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "↳".to_string(),
+        }
+    }
+}
+
+impl SuperprojectConfig {
+    /// Keys of the `superproject` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `superproject` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("superproject", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Name of the superproject when the repository is one of its submodules,
+/// e.g. `↳ parent-repo`.
+pub struct Superproject {
+    config: SuperprojectConfig,
+}
+
+impl Superproject {
+    pub fn new(config: SuperprojectConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Superproject {
+    fn name(&self) -> &'static str {
+        "superproject"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        // The git dir of a submodule lives under `.git/modules/` of its
+        // superproject, so anything else can't be a submodule.
+        let is_module = ctx
+            .repo
+            .path()
+            .components()
+            .any(|component| component == Component::Normal("modules".as_ref()));
+        if !is_module {
+            return None;
+        }
+
+        let workdir = ctx.repo.workdir()?;
+        let parent = repo::discover(workdir.parent()?).ok()?;
+        let parent_workdir = parent.workdir()?;
+
+        let relative = workdir.strip_prefix(parent_workdir).ok()?;
+        let is_submodule = parent
+            .submodules()
+            .ok()?
+            .iter()
+            .any(|submodule| submodule.path() == relative);
+        if !is_submodule {
+            return None;
+        }
+
+        let name = parent_workdir.file_name()?.to_string_lossy();
+        Some(format!("{} {name}", self.config.symbol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Repository;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = SuperprojectConfig::default();
+        config.update(&record("symbol = \"in\"")).unwrap();
+
+        assert_eq!(config.symbol, "in");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = SuperprojectConfig::default();
+        assert!(config.update(&record("name = true")).is_err());
+    }
+
+    #[test]
+    fn renders_the_superproject_of_a_submodule() {
+        let source = TempRepo::new();
+        source.commit_file("lib", "lib\n");
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.add_submodule(&source, "one");
+        let superproject = Superproject::new(SuperprojectConfig::default());
+        assert_eq!(repo.render(&superproject), None);
+        assert_eq!(source.render(&superproject), None);
+
+        let path = repo.path().join("one");
+        let child = Repository::open(&path).unwrap();
+        let name = repo.path().file_name().unwrap().to_string_lossy();
+        assert_eq!(
+            superproject.render(&Context::new(&child, &path)),
+            Some(format!("↳ {name}"))
+        );
+    }
+}
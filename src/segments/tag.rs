@@ -0,0 +1,118 @@
+use git2::{DescribeFormatOptions, DescribeOptions, ObjectType};
+
+use crate::context::Context;
+
+/// Most recent tag reachable from HEAD, as printed by
+/// `git describe --tags --abbrev=0`, or only a tag pointing exactly at HEAD
+/// when `exact` is set.
+pub fn describe(ctx: &Context, exact: bool) -> Option<String> {
+    let mut options = DescribeOptions::new();
+    options.describe_tags();
+    if exact {
+        options.max_candidates_tags(0);
+    }
+
+    // Without an abbreviated id, only the tag name is left.
+    let mut format = DescribeFormatOptions::new();
+    format.abbreviated_size(0);
+
+    ctx.repo.describe(&options).ok()?.format(Some(&format)).ok()
+}
+
+/// `git describe --tags --long` computed in-process, e.g. `v1.2.3-4-gabc1234`.
+pub fn describe_long(ctx: &Context) -> Option<String> {
+    let mut options = DescribeOptions::new();
+    options.describe_tags();
+
+    let mut format = DescribeFormatOptions::new();
+    format.abbreviated_size(7).always_use_long_format(true);
+
+    ctx.repo.describe(&options).ok()?.format(Some(&format)).ok()
+}
+
+/// Most recent tag reachable from HEAD and the number of commits since it.
+pub fn nearest(ctx: &Context) -> Option<(String, usize)> {
+    let long = describe_long(ctx)?;
+
+    // `<tag>-<distance>-g<id>`, where the tag itself may contain dashes.
+    let mut parts = long.rsplitn(3, '-');
+    let _id = parts.next()?;
+    let distance = parts.next()?.parse().ok()?;
+    let name = parts.next()?;
+
+    Some((name.to_string(), distance))
+}
+
+/// Whether `refs/tags/<name>` points at a tag object rather than directly at
+/// a commit.
+pub fn is_annotated(ctx: &Context, name: &str) -> bool {
+    let Ok(oid) = ctx.repo.refname_to_id(&format!("refs/tags/{name}")) else {
+        return false;
+    };
+
+    ctx.repo
+        .odb()
+        .and_then(|odb| odb.read_header(oid))
+        .is_ok_and(|(_, kind)| kind == ObjectType::Tag)
+}
+
+/// Names of every tag pointing at the HEAD commit, sorted.
+pub fn head_tags(ctx: &Context) -> Vec<String> {
+    let Ok(head) = ctx.repo.head().and_then(|head| head.peel_to_commit()) else {
+        return Vec::new();
+    };
+
+    let Ok(names) = ctx.repo.tag_names(None) else {
+        return Vec::new();
+    };
+
+    names
+        .iter()
+        .flatten()
+        .filter(|name| {
+            ctx.repo
+                .find_reference(&format!("refs/tags/{name}"))
+                .and_then(|reference| reference.peel_to_commit())
+                .is_ok_and(|commit| commit.id() == head.id())
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn describes_the_nearest_tag() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let ctx = Context::new(&repo.repo, repo.path());
+        assert_eq!(describe(&ctx, false), None);
+
+        repo.tag("v1.0-rc", true);
+        repo.commit_file("b", "b");
+        repo.commit_file("c", "c");
+
+        assert_eq!(describe(&ctx, false).as_deref(), Some("v1.0-rc"));
+        assert_eq!(describe(&ctx, true), None);
+        assert_eq!(nearest(&ctx), Some(("v1.0-rc".to_string(), 2)));
+    }
+
+    #[test]
+    fn lists_the_tags_at_head() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        repo.tag("old", false);
+        repo.commit_file("b", "b");
+        repo.tag("v2", false);
+        repo.tag("v1", true);
+        let ctx = Context::new(&repo.repo, repo.path());
+
+        assert_eq!(head_tags(&ctx), ["v1", "v2"]);
+        assert!(is_annotated(&ctx, "v1"));
+        assert!(!is_annotated(&ctx, "v2"));
+        assert!(!is_annotated(&ctx, "missing"));
+    }
+}
@@ -0,0 +1,139 @@
+use git2::{BranchType, Oid};
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct UnpublishedConfig {
+    pub symbol: String,
+}
+
+impl Default for UnpublishedConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "unpublished".to_string(),
+        }
+    }
+}
+
+impl UnpublishedConfig {
+    /// Keys of the `unpublished` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `unpublished` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("unpublished", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Shown when the commit HEAD points at isn't reachable from any
+/// remote-tracking branch. Unlike the divergence it works on detached HEADs
+/// and on branches without an upstream.
+pub struct Unpublished {
+    config: UnpublishedConfig,
+}
+
+impl Unpublished {
+    pub fn new(config: UnpublishedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Unpublished {
+    fn name(&self) -> &'static str {
+        "unpublished"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let head = head_commit(ctx)?;
+        let tips: Vec<String> = remote_tips(ctx).iter().map(Oid::to_string).collect();
+
+        Some(format!("{head} {}", tips.join(",")))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let head = head_commit(ctx)?;
+
+        let published = remote_tips(ctx)
+            .into_iter()
+            .any(|tip| tip == head || ctx.repo.graph_descendant_of(tip, head).unwrap_or(false));
+
+        (!published).then(|| self.config.symbol.clone())
+    }
+}
+
+fn head_commit(ctx: &Context) -> Option<Oid> {
+    ctx.repo
+        .head()
+        .ok()?
+        .peel_to_commit()
+        .ok()
+        .map(|commit| commit.id())
+}
+
+/// Commits the remote-tracking branches point at.
+fn remote_tips(ctx: &Context) -> Vec<Oid> {
+    let Ok(branches) = ctx.repo.branches(Some(BranchType::Remote)) else {
+        return Vec::new();
+    };
+
+    branches
+        .flatten()
+        .filter_map(|(branch, _)| branch.get().target())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = UnpublishedConfig::default();
+        config.update(&record("symbol = \"new\"")).unwrap();
+
+        assert_eq!(config.symbol, "new");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = UnpublishedConfig::default();
+        assert!(config.update(&record("remote = \"origin\"")).is_err());
+    }
+
+    #[test]
+    fn renders_until_a_remote_branch_contains_head() {
+        let repo = TempRepo::new();
+        let unpublished = Unpublished::new(UnpublishedConfig::default());
+        assert_eq!(repo.render(&unpublished), None);
+
+        let first = repo.commit_file("a", "a");
+        assert_eq!(repo.render(&unpublished).as_deref(), Some("unpublished"));
+
+        let second = repo.commit_file("a", "b");
+        repo.repo
+            .reference("refs/remotes/origin/topic", first, true, "test")
+            .unwrap();
+        assert_eq!(repo.render(&unpublished).as_deref(), Some("unpublished"));
+
+        // Detached at a commit a remote branch has moved past.
+        repo.repo
+            .reference("refs/remotes/origin/topic", second, true, "test")
+            .unwrap();
+        repo.repo.set_head_detached(first).unwrap();
+        assert_eq!(repo.render(&unpublished), None);
+    }
+}
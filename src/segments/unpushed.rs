@@ -0,0 +1,137 @@
+use git2::{BranchType, Oid};
+
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{Cost, SegmentProvider};
+
+#[derive(Debug, Clone)]
+pub struct UnpushedConfig {
+    pub symbol: String,
+}
+
+impl Default for UnpushedConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "⇡*".to_string(),
+        }
+    }
+}
+
+impl UnpushedConfig {
+    /// Keys of the `unpushed` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `unpushed` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("unpushed", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Shown when any local branch, not only the current one, has commits its
+/// upstream doesn't.
+pub struct Unpushed {
+    config: UnpushedConfig,
+}
+
+impl Unpushed {
+    pub fn new(config: UnpushedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Unpushed {
+    fn name(&self) -> &'static str {
+        "unpushed"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let pairs: Vec<String> = tracked_branches(ctx)
+            .iter()
+            .map(|(local, upstream)| format!("{local}:{upstream}"))
+            .collect();
+
+        Some(pairs.join(","))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let unpushed = tracked_branches(ctx).into_iter().any(|(local, upstream)| {
+            ctx.repo
+                .graph_ahead_behind(local, upstream)
+                .is_ok_and(|(ahead, _)| ahead > 0)
+        });
+
+        unpushed.then(|| self.config.symbol.clone())
+    }
+}
+
+/// Commits of every local branch that has an upstream, and of the upstream.
+fn tracked_branches(ctx: &Context) -> Vec<(Oid, Oid)> {
+    let Ok(branches) = ctx.repo.branches(Some(BranchType::Local)) else {
+        return Vec::new();
+    };
+
+    branches
+        .flatten()
+        .filter_map(|(branch, _)| {
+            let local = branch.get().target()?;
+            let upstream = branch.upstream().ok()?.get().target()?;
+            Some((local, upstream))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = UnpushedConfig::default();
+        config.update(&record("symbol = \"*\"")).unwrap();
+
+        assert_eq!(config.symbol, "*");
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = UnpushedConfig::default();
+        assert!(config.update(&record("symbol = 1.5")).is_err());
+    }
+
+    #[test]
+    fn renders_when_any_branch_is_ahead() {
+        let repo = TempRepo::new();
+        let first = repo.commit_file("a", "a");
+        let second = repo.commit_file("a", "b");
+        let unpushed = Unpushed::new(UnpushedConfig::default());
+        assert_eq!(repo.render(&unpushed), None);
+
+        repo.set_upstream("https://example.com/repo.git", second);
+        assert_eq!(repo.render(&unpushed), None);
+
+        repo.repo
+            .branch("topic", &repo.repo.find_commit(second).unwrap(), false)
+            .unwrap();
+        repo.repo
+            .reference("refs/remotes/origin/topic", first, true, "test")
+            .unwrap();
+        let mut config = repo.repo.config().unwrap();
+        config.set_str("branch.topic.remote", "origin").unwrap();
+        config
+            .set_str("branch.topic.merge", "refs/heads/topic")
+            .unwrap();
+        assert_eq!(repo.render(&unpushed).as_deref(), Some("⇡*"));
+    }
+}
@@ -0,0 +1,191 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::{join_counts, SegmentProvider};
+use crate::status::GitStatus;
+
+#[derive(Debug, Clone)]
+pub struct UnstagedConfig {
+    pub new: String,
+    pub modified: String,
+    pub renamed: String,
+    pub typechange: String,
+}
+
+impl Default for UnstagedConfig {
+    fn default() -> Self {
+        Self {
+            new: "?".to_string(),
+            modified: "~".to_string(),
+            renamed: "->".to_string(),
+            typechange: "t".to_string(),
+        }
+    }
+}
+
+impl UnstagedConfig {
+    /// Keys of the `unstaged` record.
+    pub const KEYS: &'static [&'static str] = &["new", "modified", "renamed", "typechange"];
+
+    /// Applies the settings of the `unstaged` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("unstaged", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "new", &mut self.new)?;
+        set_string(record, "modified", &mut self.modified)?;
+        set_string(record, "renamed", &mut self.renamed)?;
+        set_string(record, "typechange", &mut self.typechange)?;
+
+        Ok(())
+    }
+}
+
+/// Counts of changes in the working tree that are not staged yet.
+pub struct Unstaged {
+    config: UnstagedConfig,
+}
+
+impl Unstaged {
+    pub fn new(config: UnstagedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Unstaged {
+    fn name(&self) -> &'static str {
+        "unstaged"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let status = ctx.status()?;
+
+        join_counts(&[
+            (&self.config.new, status.wt_new),
+            (&self.config.modified, status.wt_modified),
+            (&self.config.renamed, status.wt_renamed),
+            (&self.config.typechange, status.wt_typechange),
+        ])
+    }
+}
+
+/// A single count of the `unstaged` segment, for the `$modified`,
+/// `$untracked` and `$renamed` variables of starship formats.
+pub struct UnstagedCount {
+    name: &'static str,
+    symbol: String,
+    count: fn(&GitStatus) -> u32,
+}
+
+impl UnstagedCount {
+    /// Modified files not staged.
+    pub fn modified(config: &UnstagedConfig) -> Self {
+        Self {
+            name: "modified",
+            symbol: config.modified.clone(),
+            count: |status| status.wt_modified,
+        }
+    }
+
+    /// Untracked files.
+    pub fn untracked(config: &UnstagedConfig) -> Self {
+        Self {
+            name: "untracked",
+            symbol: config.new.clone(),
+            count: |status| status.wt_new,
+        }
+    }
+
+    /// Renamed files not staged.
+    pub fn renamed(config: &UnstagedConfig) -> Self {
+        Self {
+            name: "renamed",
+            symbol: config.renamed.clone(),
+            count: |status| status.wt_renamed,
+        }
+    }
+}
+
+impl SegmentProvider for UnstagedCount {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let status = ctx.status()?;
+        join_counts(&[(&self.symbol, (self.count)(status))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = UnstagedConfig::default();
+        config
+            .update(&record("new = \"U\"\nmodified = \"M\""))
+            .unwrap();
+
+        assert_eq!(config.new, "U");
+        assert_eq!(config.modified, "M");
+        assert_eq!(config.typechange, "t");
+    }
+
+    #[test]
+    fn update_rejects_wrong_types() {
+        let mut config = UnstagedConfig::default();
+        assert!(config.update(&record("new = 1")).is_err());
+    }
+
+    #[test]
+    fn renders_the_unstaged_counts() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a\n");
+        let unstaged = Unstaged::new(UnstagedConfig::default());
+        assert_eq!(repo.render(&unstaged), None);
+
+        repo.write("a", "changed\n");
+        repo.write("b", "b\n");
+        repo.write("dir/c", "c\n");
+        assert_eq!(repo.render(&unstaged).as_deref(), Some("?2 ~1"));
+    }
+
+    #[test]
+    fn renders_single_counts() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a\n");
+        repo.write("a", "changed\n");
+        repo.write("b", "b\n");
+        repo.write("c", "c\n");
+
+        let config = UnstagedConfig::default();
+        assert_eq!(
+            repo.render(&UnstagedCount::modified(&config)).as_deref(),
+            Some("~1")
+        );
+        assert_eq!(
+            repo.render(&UnstagedCount::untracked(&config)).as_deref(),
+            Some("?2")
+        );
+        assert_eq!(repo.render(&UnstagedCount::renamed(&config)), None);
+    }
+
+    #[test]
+    fn follows_status_show_untracked_files() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a\n");
+        repo.write("dir/b", "b\n");
+        repo.write("dir/c", "c\n");
+        repo.repo
+            .config()
+            .unwrap()
+            .set_str("status.showUntrackedFiles", "no")
+            .unwrap();
+
+        assert_eq!(repo.render(&Unstaged::new(UnstagedConfig::default())), None);
+    }
+}
@@ -0,0 +1,182 @@
+use std::fs;
+use std::path::Path;
+
+use nu_protocol::{LabeledError, Span, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::{file_stamp, Context};
+use crate::segments::{Cost, SegmentProvider};
+use crate::toml;
+
+const MANIFESTS: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml"];
+
+#[derive(Debug, Clone)]
+pub struct VersionConfig {
+    pub symbol: String,
+}
+
+impl Default for VersionConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "v".to_string(),
+        }
+    }
+}
+
+impl VersionConfig {
+    /// Keys of the `version` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `version` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("version", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Version of the project at the repository root, read from the first of
+/// `Cargo.toml`, `package.json` or `pyproject.toml` that declares one. Hidden
+/// when the version is inherited from a Cargo workspace.
+pub struct Version {
+    config: VersionConfig,
+}
+
+impl Version {
+    pub fn new(config: VersionConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Version {
+    fn name(&self) -> &'static str {
+        "version"
+    }
+
+    fn cost(&self) -> Cost {
+        Cost::Expensive
+    }
+
+    fn cache_key(&self, ctx: &Context) -> Option<String> {
+        let workdir = ctx.repo.workdir()?;
+        let stamps: Vec<String> = MANIFESTS
+            .iter()
+            .map(|manifest| file_stamp(&workdir.join(manifest)))
+            .collect();
+
+        Some(stamps.join(":"))
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        let workdir = ctx.repo.workdir()?;
+        let version = MANIFESTS
+            .iter()
+            .find_map(|manifest| read_version(&workdir.join(manifest)))??;
+
+        Some(format!("{}{version}", self.config.symbol))
+    }
+}
+
+/// `None` when the manifest doesn't declare a version, `Some(None)` when it
+/// inherits one from a workspace the prompt can't see.
+fn read_version(path: &Path) -> Option<Option<String>> {
+    let contents = fs::read_to_string(path).ok()?;
+
+    match path.file_name()?.to_str()? {
+        "package.json" => {
+            let json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+            json.get("version")?
+                .as_str()
+                .map(|version| Some(version.to_string()))
+        }
+        "Cargo.toml" => toml_version(&contents, &[&["package"]]),
+        "pyproject.toml" => toml_version(&contents, &[&["project"], &["tool", "poetry"]]),
+        _ => None,
+    }
+}
+
+/// Finds the `version` key of the first of the given TOML tables declaring
+/// one.
+fn toml_version(contents: &str, tables: &[&[&str]]) -> Option<Option<String>> {
+    let document = toml::parse(contents, Span::unknown()).ok()?;
+
+    tables.iter().find_map(|table| {
+        let mut record = &document;
+        for key in *table {
+            record = record.get(key)?.as_record().ok()?;
+        }
+
+        match record.get("version")? {
+            Value::String { val, .. } if !val.is_empty() => Some(Some(val.clone())),
+            // `version = { workspace = true }` or `version.workspace = true`.
+            Value::Record { .. } => Some(None),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = VersionConfig::default();
+        config.update(&record("symbol = \"@\"")).unwrap();
+
+        assert_eq!(config.symbol, "@");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = VersionConfig::default();
+        assert!(config.update(&record("manifest = \"Cargo.toml\"")).is_err());
+    }
+
+    #[test]
+    fn toml_version_reads_the_first_table_declaring_one() {
+        let tables: &[&[&str]] = &[&["project"], &["tool", "poetry"]];
+        let poetry = "[tool.poetry]\nversion = \"0.3.0\"";
+        assert_eq!(
+            toml_version(poetry, tables),
+            Some(Some("0.3.0".to_string()))
+        );
+
+        let inherited = "[package]\nversion.workspace = true";
+        assert_eq!(toml_version(inherited, &[&["package"]]), Some(None));
+        assert_eq!(
+            toml_version("[package]\nname = \"a\"", &[&["package"]]),
+            None
+        );
+    }
+
+    #[test]
+    fn renders_the_manifest_version() {
+        let repo = TempRepo::new();
+        let version = Version::new(VersionConfig::default());
+        assert_eq!(repo.render(&version), None);
+
+        repo.write("package.json", r#"{"version": "2.0.0"}"#);
+        assert_eq!(repo.render(&version).as_deref(), Some("v2.0.0"));
+
+        repo.write("Cargo.toml", "[package]\nversion = \"1.4.0\"");
+        assert_eq!(repo.render(&version).as_deref(), Some("v1.4.0"));
+
+        // Values the prompt has no use for, such as dates, don't hide it.
+        repo.write(
+            "pyproject.toml",
+            "[project]\nversion = \"0.3.0\"\n\n[tool.release]\ndate = 2024-05-01",
+        );
+        repo.remove("package.json");
+        repo.remove("Cargo.toml");
+        assert_eq!(repo.render(&version).as_deref(), Some("v0.3.0"));
+
+        // An inherited version hides the segment rather than falling back.
+        repo.write("Cargo.toml", "[package]\nversion = { workspace = true }");
+        assert_eq!(repo.render(&version), None);
+    }
+}
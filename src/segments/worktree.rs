@@ -0,0 +1,99 @@
+use nu_protocol::{LabeledError, Value};
+
+use crate::config::{check_keys, section, set_string};
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+
+#[derive(Debug, Clone)]
+pub struct WorktreeConfig {
+    pub symbol: String,
+}
+
+impl Default for WorktreeConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "wt:".to_string(),
+        }
+    }
+}
+
+impl WorktreeConfig {
+    /// Keys of the `worktree` record.
+    pub const KEYS: &'static [&'static str] = &["symbol"];
+
+    /// Applies the settings of the `worktree` record of the plugin config.
+    pub fn update(&mut self, value: &Value) -> Result<(), LabeledError> {
+        let record = section("worktree", value)?;
+        check_keys(record, Self::KEYS)?;
+
+        set_string(record, "symbol", &mut self.symbol)?;
+
+        Ok(())
+    }
+}
+
+/// Name of the linked worktree, the directory under `.git/worktrees/`. The
+/// main checkout shows nothing.
+pub struct Worktree {
+    config: WorktreeConfig,
+}
+
+impl Worktree {
+    pub fn new(config: WorktreeConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SegmentProvider for Worktree {
+    fn name(&self) -> &'static str {
+        "worktree"
+    }
+
+    fn render(&self, ctx: &Context) -> Option<String> {
+        if !ctx.repo.is_worktree() {
+            return None;
+        }
+
+        let name = ctx.repo.path().file_name()?.to_string_lossy();
+
+        Some(format!("{}{name}", self.config.symbol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Repository;
+
+    use super::*;
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn update_reads_the_record() {
+        let mut config = WorktreeConfig::default();
+        config.update(&record("symbol = \"⌥ \"")).unwrap();
+
+        assert_eq!(config.symbol, "⌥ ");
+    }
+
+    #[test]
+    fn update_rejects_unknown_keys() {
+        let mut config = WorktreeConfig::default();
+        assert!(config.update(&record("main = \"\"")).is_err());
+    }
+
+    #[test]
+    fn renders_the_name_of_linked_worktrees() {
+        let repo = TempRepo::new();
+        repo.commit_file("a", "a");
+        let worktree = Worktree::new(WorktreeConfig::default());
+        assert_eq!(repo.render(&worktree), None);
+
+        let path = repo.path().join("linked");
+        repo.repo.worktree("feature", &path, None).unwrap();
+        let linked = Repository::open(&path).unwrap();
+        assert_eq!(
+            worktree.render(&Context::new(&linked, &path)).as_deref(),
+            Some("wt:feature")
+        );
+    }
+}
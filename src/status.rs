@@ -0,0 +1,225 @@
+use std::ops::BitAnd;
+
+use git2::{Repository, Status, StatusOptions};
+
+#[derive(Debug, Default, Clone)]
+pub struct GitStatus {
+    pub index_new: u32,
+    pub index_modified: u32,
+    pub index_deleted: u32,
+    pub index_renamed: u32,
+    pub index_typechange: u32,
+
+    pub wt_new: u32,
+    pub wt_modified: u32,
+    pub wt_deleted: u32,
+    pub wt_renamed: u32,
+    pub wt_typechange: u32,
+
+    pub ignored: u32,
+    pub conflicted: u32,
+}
+
+impl GitStatus {
+    pub fn init(repo: &Repository) -> Option<Self> {
+        let statuses = match repo.statuses(Some(&mut status_options(repo))) {
+            Ok(statuses) => statuses,
+            Err(_) => {
+                return None;
+            }
+        };
+
+        let mut git_status = Self::default();
+
+        statuses.iter().for_each(|status_entry| {
+            let status = status_entry.status();
+
+            if check(status, Status::INDEX_NEW) {
+                git_status.index_new += 1;
+            }
+
+            if check(status, Status::INDEX_MODIFIED) {
+                git_status.index_modified += 1;
+            }
+
+            if check(status, Status::INDEX_DELETED) {
+                git_status.index_deleted += 1;
+            }
+
+            if check(status, Status::INDEX_RENAMED) {
+                git_status.index_renamed += 1;
+            }
+
+            if check(status, Status::INDEX_TYPECHANGE) {
+                git_status.index_typechange += 1;
+            }
+
+            if check(status, Status::WT_NEW) {
+                git_status.wt_new += 1;
+            }
+
+            if check(status, Status::WT_MODIFIED) {
+                git_status.wt_modified += 1;
+            }
+
+            if check(status, Status::WT_DELETED) {
+                git_status.wt_deleted += 1;
+            }
+
+            if check(status, Status::WT_RENAMED) {
+                git_status.wt_renamed += 1;
+            }
+
+            if check(status, Status::WT_TYPECHANGE) {
+                git_status.wt_typechange += 1;
+            }
+
+            if check(status, Status::IGNORED) {
+                git_status.ignored += 1;
+            }
+
+            if check(status, Status::CONFLICTED) {
+                git_status.conflicted += 1;
+            }
+        });
+
+        Some(git_status)
+    }
+}
+
+/// How far untracked files are looked for, from `status.showUntrackedFiles`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Untracked {
+    /// Untracked files aren't looked for at all.
+    No,
+    /// Untracked directories are listed without walking into them.
+    Normal,
+    /// Every untracked file is listed.
+    All,
+}
+
+impl Untracked {
+    /// The setting of `repo`, `All` when it isn't set. Like git, a boolean
+    /// means `normal` or `no`.
+    pub fn of(repo: &Repository) -> Self {
+        let value = repo
+            .config()
+            .and_then(|config| config.get_string("status.showUntrackedFiles"));
+
+        match value.as_deref().map(str::to_lowercase).as_deref() {
+            Ok("no" | "false" | "off" | "0") => Self::No,
+            Ok("normal" | "true" | "on" | "yes" | "1") => Self::Normal,
+            _ => Self::All,
+        }
+    }
+}
+
+/// Whether `repo` detects renames, from `status.renames` or else
+/// `diff.renames`. Both default to true, and `copies` counts as true.
+pub fn detects_renames(repo: &Repository) -> bool {
+    let Ok(config) = repo.config() else {
+        return true;
+    };
+
+    let value = config
+        .get_string("status.renames")
+        .or_else(|_| config.get_string("diff.renames"));
+
+    !matches!(
+        value.as_deref().map(str::to_lowercase).as_deref(),
+        Ok("false" | "no" | "off" | "0")
+    )
+}
+
+/// Options of the status scan of `repo`, shared by the counts and
+/// `git_prompt files`.
+pub fn status_options(repo: &Repository) -> StatusOptions {
+    let untracked = Untracked::of(repo);
+    let renames = detects_renames(repo);
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(untracked != Untracked::No)
+        .recurse_untracked_dirs(untracked == Untracked::All)
+        .renames_head_to_index(renames)
+        .renames_index_to_workdir(renames);
+
+    status_options
+}
+
+/// Check the bits of a flag against the value to see if they are set
+#[inline]
+fn check<B>(val: B, flag: B) -> bool
+where
+    B: BitAnd<Output = B> + PartialEq + Copy,
+{
+    val & flag == flag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn counts_the_index_and_the_working_tree() {
+        let repo = TempRepo::new();
+        repo.commit_file("kept", "a");
+        repo.commit_file("gone", "a");
+        repo.commit_file("edited", "a");
+
+        repo.write("added", "a");
+        repo.stage("added");
+        repo.write("kept", "b");
+        repo.stage("kept");
+        repo.remove("gone");
+        repo.write("edited", "longer");
+        repo.write("new", "new");
+
+        let status = GitStatus::init(&repo.repo).unwrap();
+        assert_eq!(status.index_new, 1);
+        assert_eq!(status.index_modified, 1);
+        assert_eq!(status.wt_deleted, 1);
+        assert_eq!(status.wt_modified, 1);
+        assert_eq!(status.wt_new, 1);
+        assert_eq!(status.conflicted, 0);
+    }
+
+    #[test]
+    fn untracked_follows_show_untracked_files() {
+        let repo = TempRepo::new();
+        assert_eq!(Untracked::of(&repo.repo), Untracked::All);
+
+        let mut config = repo.repo.config().unwrap();
+        for (value, expected) in [
+            ("no", Untracked::No),
+            ("false", Untracked::No),
+            ("normal", Untracked::Normal),
+            ("true", Untracked::Normal),
+            ("all", Untracked::All),
+        ] {
+            config.set_str("status.showUntrackedFiles", value).unwrap();
+            assert_eq!(Untracked::of(&repo.repo), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn renames_follow_status_renames_then_diff_renames() {
+        let repo = TempRepo::new();
+        repo.commit_file("old", "contents of a renamed file");
+        std::fs::rename(repo.path().join("old"), repo.path().join("new")).unwrap();
+
+        assert!(detects_renames(&repo.repo));
+        let status = GitStatus::init(&repo.repo).unwrap();
+        assert_eq!((status.wt_renamed, status.wt_deleted), (1, 0));
+
+        let mut config = repo.repo.config().unwrap();
+        config.set_str("diff.renames", "false").unwrap();
+        assert!(!detects_renames(&repo.repo));
+        let status = GitStatus::init(&repo.repo).unwrap();
+        assert_eq!((status.wt_renamed, status.wt_deleted), (0, 1));
+
+        config.set_str("status.renames", "copies").unwrap();
+        assert!(detects_renames(&repo.repo));
+    }
+}
@@ -0,0 +1,231 @@
+//! Temporary repositories and config records for the tests.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use git2::{ObjectType, Oid, Repository, RepositoryInitOptions, ResetType, Signature};
+use nu_plugin_test_support::PluginTest;
+use nu_protocol::{ShellError, Span, Value};
+
+use crate::context::Context;
+use crate::segments::SegmentProvider;
+use crate::toml;
+use crate::GitPromptPlugin;
+
+/// Repository in a directory of its own under the temp dir, removed when
+/// dropped. Its branch is `main` and it has an identity, whatever the global
+/// git config says.
+pub struct TempRepo {
+    pub repo: Repository,
+    dir: PathBuf,
+}
+
+impl TempRepo {
+    pub fn new() -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "nu_plugin_git_prompt-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+
+        let repo = Repository::init_opts(&dir, RepositoryInitOptions::new().initial_head("main"))
+            .expect("the temp repository can be created");
+        let mut config = repo.config().expect("the repository has a config");
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        Self { repo, dir }
+    }
+
+    /// Root of the working tree.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes `contents` to `path`, relative to the working tree.
+    pub fn write(&self, path: &str, contents: &str) {
+        let path = self.dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, contents).unwrap();
+    }
+
+    /// Deletes `path` from the working tree.
+    pub fn remove(&self, path: &str) {
+        fs::remove_file(self.dir.join(path)).unwrap();
+    }
+
+    /// Adds `path` to the index.
+    pub fn stage(&self, path: &str) {
+        let mut index = self.repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+    }
+
+    /// Removes `path` from the index.
+    pub fn unstage(&self, path: &str) {
+        let mut index = self.repo.index().unwrap();
+        index.remove_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+    }
+
+    /// Commits the index on top of HEAD.
+    pub fn commit(&self, message: &str) -> Oid {
+        let mut index = self.repo.index().unwrap();
+        let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parent = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+
+        self.repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+    }
+
+    /// Writes, stages and commits `path`.
+    pub fn commit_file(&self, path: &str, contents: &str) -> Oid {
+        self.write(path, contents);
+        self.stage(path);
+        self.commit(&format!("Change {path}"))
+    }
+
+    /// Tags HEAD with `name`, with a tag object when `annotated`.
+    pub fn tag(&self, name: &str, annotated: bool) {
+        let head = self.repo.head().unwrap().peel(ObjectType::Commit).unwrap();
+        if annotated {
+            let signature = Signature::now("Test", "test@example.com").unwrap();
+            self.repo
+                .tag(name, &head, &signature, "Release", false)
+                .unwrap();
+        } else {
+            self.repo.tag_lightweight(name, &head, false).unwrap();
+        }
+    }
+
+    /// Moves `main` back to `target`, like `git reset --hard`.
+    pub fn reset(&self, target: Oid) {
+        let target = self.repo.find_object(target, None).unwrap();
+        self.repo.reset(&target, ResetType::Hard, None).unwrap();
+    }
+
+    /// Merges a `theirs` branch into `main`, leaving conflicts in the index
+    /// as `git merge` does. Both sides change the `modified` files of their
+    /// common base and add the `added` ones.
+    pub fn merge_conflict(&self, modified: &[&str], added: &[&str]) {
+        let base = self.commit_files(modified, "base");
+
+        let theirs = self.commit_files(&[modified, added].concat(), "theirs");
+        let branch = self
+            .repo
+            .branch("theirs", &self.repo.find_commit(theirs).unwrap(), true)
+            .unwrap();
+
+        self.reset(base);
+        self.commit_files(&[modified, added].concat(), "ours");
+
+        // Merged by its branch, so `MERGE_MSG` names it.
+        let theirs = self
+            .repo
+            .reference_to_annotated_commit(branch.get())
+            .unwrap();
+        self.repo.merge(&[&theirs], None, None).unwrap();
+    }
+
+    /// Writes `contents` to every one of `paths` and commits them.
+    fn commit_files(&self, paths: &[&str], contents: &str) -> Oid {
+        for path in paths {
+            self.write(path, contents);
+            self.stage(path);
+        }
+        self.commit(contents)
+    }
+
+    /// Clones `source` as a submodule at `path` and commits it, returning the
+    /// repository of the submodule.
+    pub fn add_submodule(&self, source: &TempRepo, path: &str) -> Repository {
+        let url = source.path().to_str().unwrap();
+        let mut submodule = self.repo.submodule(url, Path::new(path), true).unwrap();
+        let repo = submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        self.commit(&format!("Add {path}"));
+        repo
+    }
+
+    /// Makes `origin/main`, pointing at `target`, the upstream of `main`.
+    pub fn set_upstream(&self, url: &str, target: Oid) {
+        if self.repo.find_remote("origin").is_err() {
+            self.repo.remote("origin", url).unwrap();
+        }
+        self.repo
+            .reference("refs/remotes/origin/main", target, true, "test")
+            .unwrap();
+
+        let mut config = self.repo.config().unwrap();
+        config.set_str("branch.main.remote", "origin").unwrap();
+        config
+            .set_str("branch.main.merge", "refs/heads/main")
+            .unwrap();
+    }
+
+    /// Renders `provider` from the root of the working tree.
+    pub fn render(&self, provider: &dyn SegmentProvider) -> Option<String> {
+        provider.render(&Context::new(&self.repo, &self.dir))
+    }
+}
+
+impl Drop for TempRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Record of the TOML in `text`, shaped like a section of the plugin config.
+pub fn record(text: &str) -> Value {
+    let record = toml::parse(text, Span::test_data()).expect("the test TOML is valid");
+    Value::record(record, Span::test_data())
+}
+
+/// Evaluates `source` with the commands of the plugin in scope. Errors are
+/// debug-printed, so tests can look for their message and labels.
+pub fn eval(source: &str) -> Result<Value, String> {
+    eval_with(&Arc::new(GitPromptPlugin::default()), source)
+}
+
+/// Evaluates `source` like [`eval`], against `plugin` so its caches outlive
+/// the call.
+pub fn eval_with(plugin: &Arc<GitPromptPlugin>, source: &str) -> Result<Value, String> {
+    let debug = |err: ShellError| format!("{err:?}");
+    PluginTest::new("git_prompt", plugin.clone())
+        .map_err(debug)?
+        .eval(source)
+        .map_err(debug)?
+        .into_value(Span::test_data())
+        .map_err(debug)
+}
+
+/// Values of `column` in every row of `table`.
+pub fn column(table: &Value, column: &str) -> Vec<Value> {
+    table
+        .as_list()
+        .expect("the value is a table")
+        .iter()
+        .map(|row| row.as_record().unwrap().get(column).cloned().unwrap())
+        .collect()
+}
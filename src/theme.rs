@@ -0,0 +1,117 @@
+use crate::config::Config;
+
+/// Names accepted by `--theme` and the `theme` setting.
+pub const THEMES: &[&str] = &["default", "ascii"];
+
+/// Replaces the symbols in `config` with the ones of `theme`. The `ascii`
+/// theme is for terminals without Unicode or Nerd Fonts: nothing it renders
+/// is outside ASCII.
+pub fn apply(config: &mut Config, theme: &str) -> Result<(), String> {
+    match theme {
+        "default" => {}
+        "ascii" => {
+            config.unsafe_owner = "! unsafe owner".to_string();
+            config.stale = "(stale)".to_string();
+            config.remote.symbol = "@".to_string();
+            config.remote.icons.clear();
+            config.remote.local = "local".to_string();
+            config.remote.ellipsis = "...".to_string();
+            config.branch.more_tags = "...".to_string();
+            config.branch.annotated_symbol = "T:".to_string();
+            config.branch.lightweight_symbol = "t:".to_string();
+            config.state.todo_symbol = "...".to_string();
+            config.divergence.ahead = "^".to_string();
+            config.divergence.behind = "v".to_string();
+            config.divergence.push_ahead = ">".to_string();
+            config.divergence.push_behind = "<".to_string();
+            config.stash.symbol = "$".to_string();
+            config.default_branch.ahead = "^".to_string();
+            config.default_branch.behind = "v".to_string();
+            config.subject.ellipsis = "...".to_string();
+            config.description.ellipsis = "...".to_string();
+            config.signature.signed = "sig".to_string();
+            config.signature.unsigned = "unsigned".to_string();
+            config.signature.verified = "sig:ok".to_string();
+            config.signature.invalid = "sig:bad".to_string();
+            config.identity.mismatch = "!".to_string();
+            config.identity.missing = "! no identity".to_string();
+            config.lock.symbol = "locked".to_string();
+            config.diffstat.deletions = "-".to_string();
+            config.submodules.outdated = "sub^".to_string();
+            config.superproject.symbol = "in:".to_string();
+            config.unpushed.symbol = "^*".to_string();
+            config.remotes.symbol = "<>".to_string();
+            config.fetch_age.symbol = "fetched ".to_string();
+            config.maintenance.symbol = "gc".to_string();
+        }
+        _ => {
+            return Err(format!(
+                "unknown theme `{theme}`, expected one of: {}",
+                THEMES.join(", ")
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::SegmentCache;
+    use crate::context::Context;
+    use crate::segments::{join, names, Registry};
+    use crate::testing::{record, TempRepo};
+
+    #[test]
+    fn ascii_prompt_is_ascii() {
+        let repo = TempRepo::new();
+        let base = repo.commit_file("a", "a");
+        repo.set_upstream("git@github.com:nushell/nushell.git", base);
+        repo.commit_file("b", "b");
+        for tag in ["t1", "t2", "t3", "t4"] {
+            repo.tag(tag, tag == "t1");
+        }
+        repo.write("a", "edited");
+        repo.write("new", "new");
+        repo.remove("b");
+        repo.write("staged", "staged");
+        repo.stage("staged");
+
+        let mut stash = git2::Repository::open(repo.path()).unwrap();
+        let signature = stash.signature().unwrap();
+        stash
+            .stash_save(&signature, "wip", Some(git2::StashFlags::KEEP_INDEX))
+            .unwrap();
+        repo.write("a", "edited");
+
+        let mut config = Config::default();
+        let busy = record(
+            "[branch]\nall_head_tags = true\ndistinguish_tags = true\nmax_tags = 2\n\n\
+             [remote]\nshow_upstream = true\nmax_upstream_length = 5\n\n\
+             [subject]\nmax_length = 5",
+        );
+        config.update(busy.as_record().unwrap()).unwrap();
+        apply(&mut config, "ascii").unwrap();
+
+        let segments: Vec<String> = names().iter().map(|name| name.to_string()).collect();
+        let ctx = Context::new(&repo.repo, repo.path());
+        let rendered = Registry::new(&config, &segments).render(&ctx, &SegmentCache::default());
+        let prompt = join(&rendered);
+
+        for name in ["branch", "remote", "divergence", "stash", "subject"] {
+            assert!(
+                rendered.iter().any(|segment| segment.name == name),
+                "{name} in {prompt:?}"
+            );
+        }
+        assert!(prompt.is_ascii(), "{prompt:?}");
+        assert!(config.unsafe_owner.is_ascii() && config.stale.is_ascii());
+    }
+
+    #[test]
+    fn rejects_unknown_themes() {
+        let mut config = Config::default();
+        assert!(apply(&mut config, "fancy").is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nu_protocol::{Span, Value};
+
+/// Seconds since the Unix epoch.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+/// Short relative age such as `45s`, `3h` or `2d`.
+pub fn format_age(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3_600 => format!("{}m", seconds / 60),
+        3_600..86_400 => format!("{}h", seconds / 3_600),
+        86_400..2_592_000 => format!("{}d", seconds / 86_400),
+        2_592_000..31_536_000 => format!("{}mo", seconds / 2_592_000),
+        _ => format!("{}y", seconds / 31_536_000),
+    }
+}
+
+/// Time elapsed since `timestamp`, in seconds since the Unix epoch, as a
+/// nushell duration.
+pub fn age_value(timestamp: i64, span: Span) -> Value {
+    let seconds = (now() - timestamp).max(0);
+    Value::duration(seconds * 1_000_000_000, span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_age_picks_the_largest_unit() {
+        assert_eq!(format_age(0), "0s");
+        assert_eq!(format_age(59), "59s");
+        assert_eq!(format_age(60), "1m");
+        assert_eq!(format_age(3_599), "59m");
+        assert_eq!(format_age(3_600), "1h");
+        assert_eq!(format_age(86_400 * 2), "2d");
+        assert_eq!(format_age(2_592_000 * 3), "3mo");
+        assert_eq!(format_age(31_536_000 * 4), "4y");
+    }
+
+    #[test]
+    fn format_age_clamps_future_times() {
+        assert_eq!(format_age(-30), "0s");
+    }
+
+    #[test]
+    fn age_value_is_a_duration() {
+        let value = age_value(now() - 10, Span::test_data());
+        let nanos = value.as_duration().unwrap();
+        assert!((10_000_000_000..12_000_000_000).contains(&nanos));
+    }
+}
@@ -0,0 +1,132 @@
+//! Reader for the TOML config file.
+//!
+//! Documents are parsed by the `toml` crate and turned into nushell values.
+//! Dates and times are kept as their TOML text since no setting takes one.
+
+use ::toml::{Table, Value as TomlValue};
+use nu_protocol::{Record, Span, Value};
+
+/// Parses `text` into a record, with every value spanning `span`. Errors
+/// carry the line they were found on.
+pub fn parse(text: &str, span: Span) -> Result<Record, String> {
+    let table: Table = ::toml::from_str(text).map_err(|err| {
+        let message = err.message().trim_end();
+        match err.span() {
+            Some(range) => {
+                let line = text[..range.start.min(text.len())].matches('\n').count() + 1;
+                format!("line {line}: {message}")
+            }
+            None => message.to_string(),
+        }
+    })?;
+
+    Ok(record(table, span))
+}
+
+fn record(table: Table, span: Span) -> Record {
+    table
+        .into_iter()
+        .map(|(key, value)| (key, convert(value, span)))
+        .collect()
+}
+
+fn convert(value: TomlValue, span: Span) -> Value {
+    match value {
+        TomlValue::String(val) => Value::string(val, span),
+        TomlValue::Integer(val) => Value::int(val, span),
+        TomlValue::Float(val) => Value::float(val, span),
+        TomlValue::Boolean(val) => Value::bool(val, span),
+        TomlValue::Datetime(val) => Value::string(val.to_string(), span),
+        TomlValue::Array(vals) => Value::list(
+            vals.into_iter().map(|val| convert(val, span)).collect(),
+            span,
+        ),
+        TomlValue::Table(table) => Value::record(record(table, span), span),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(text: &str, key: &str) -> Value {
+        let record = parse(text, Span::test_data()).unwrap();
+        record.get(key).cloned().unwrap()
+    }
+
+    #[test]
+    fn parses_every_kind_of_value() {
+        let text = r#"
+            string = "a\tb"
+            literal = 'C:\dir'
+            int = 1_000
+            hex = 0xff
+            float = 2.5
+            bool = true
+            list = [1, "two", [3]]
+            inline = { a = 1, b.c = "d" }
+        "#;
+        let record = parse(text, Span::test_data()).unwrap();
+
+        assert_eq!(record.get("string"), Some(&Value::test_string("a\tb")));
+        assert_eq!(record.get("literal"), Some(&Value::test_string("C:\\dir")));
+        assert_eq!(record.get("int"), Some(&Value::test_int(1000)));
+        assert_eq!(record.get("hex"), Some(&Value::test_int(255)));
+        assert_eq!(record.get("float"), Some(&Value::test_float(2.5)));
+        assert_eq!(record.get("bool"), Some(&Value::test_bool(true)));
+
+        let list = record.get("list").unwrap().as_list().unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[1], Value::test_string("two"));
+
+        let inline = record.get("inline").unwrap().as_record().unwrap();
+        let b = inline.get("b").unwrap().as_record().unwrap();
+        assert_eq!(b.get("c"), Some(&Value::test_string("d")));
+    }
+
+    #[test]
+    fn multi_line_strings_may_end_with_quotes() {
+        assert_eq!(get(r#"a = """x"""""#, "a"), Value::test_string("x\""));
+        assert_eq!(get("a = '''y'''''", "a"), Value::test_string("y''"));
+        assert_eq!(
+            get("a = \"\"\"\none \\\n  two\"\"\"", "a"),
+            Value::test_string("one two")
+        );
+    }
+
+    #[test]
+    fn dates_are_kept_as_text() {
+        let text = "released = 2024-05-01T10:00:00Z\nday = 2024-05-01\nversion = \"1.0\"";
+        let record = parse(text, Span::test_data()).unwrap();
+
+        assert_eq!(
+            record.get("released"),
+            Some(&Value::test_string("2024-05-01T10:00:00Z"))
+        );
+        assert_eq!(record.get("day"), Some(&Value::test_string("2024-05-01")));
+        assert_eq!(record.get("version"), Some(&Value::test_string("1.0")));
+    }
+
+    #[test]
+    fn tables_nest_by_their_keys() {
+        let text = "[tool.poetry]\nversion = \"0.3.0\"\n\n[[rule]]\nname = \"a\"\n\n[[rule]]\nname = \"b\"";
+        let record = parse(text, Span::test_data()).unwrap();
+
+        let tool = record.get("tool").unwrap().as_record().unwrap();
+        let poetry = tool.get("poetry").unwrap().as_record().unwrap();
+        assert_eq!(poetry.get("version"), Some(&Value::test_string("0.3.0")));
+
+        let rules = record.get("rule").unwrap().as_list().unwrap();
+        assert_eq!(rules.len(), 2);
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        let duplicate = parse("[a]\nx = 1\n\n[a]\ny = 2", Span::test_data());
+        assert!(duplicate.unwrap_err().starts_with("line 4:"));
+
+        assert!(parse("a = 1\na = 2", Span::test_data()).is_err());
+        assert!(parse("a = \"unterminated", Span::test_data()).is_err());
+        assert!(parse("a.b = 1\n[a]\nb = 2", Span::test_data()).is_err());
+    }
+}